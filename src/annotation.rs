@@ -0,0 +1,166 @@
+use std::str::FromStr;
+
+use crate::data::{FieldState, RichText, Style};
+
+/// A formatting typed on values as an editorial mark.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mark {
+    Highlight,
+    Shading,
+    Strike,
+}
+
+impl FromStr for Mark {
+    type Err = String;
+
+    /// `highlight`, `shading` or `strike`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "highlight" => Ok(Self::Highlight),
+            "shading" => Ok(Self::Shading),
+            "strike" => Ok(Self::Strike),
+            s => Err(s.to_owned()),
+        }
+    }
+}
+
+/// What a mark says of the text it is typed on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MarkMeaning {
+    /// The value must be checked against the original.
+    Verify,
+    /// The text was withdrawn: it is left out of the value and kept as a
+    /// note.
+    Remove,
+    /// The mark means nothing, it is kept as formatting of the text.
+    Ignore,
+}
+
+impl FromStr for MarkMeaning {
+    type Err = String;
+
+    /// `verify`, `remove` or `ignore`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "verify" => Ok(Self::Verify),
+            "remove" => Ok(Self::Remove),
+            "ignore" => Ok(Self::Ignore),
+            s => Err(s.to_owned()),
+        }
+    }
+}
+
+/// How the marks typed on values are read as their [`FieldState`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnnotationRules {
+    pub highlight: MarkMeaning,
+    pub shading: MarkMeaning,
+    pub strike: MarkMeaning,
+}
+
+impl Default for AnnotationRules {
+    /// Highlighted values are to be verified and struck text is removed.
+    /// Shading, often used to color whole cells, means nothing.
+    fn default() -> Self {
+        Self {
+            highlight: MarkMeaning::Verify,
+            shading: MarkMeaning::Ignore,
+            strike: MarkMeaning::Remove,
+        }
+    }
+}
+
+impl AnnotationRules {
+    pub fn set(&mut self, mark: Mark, meaning: MarkMeaning) {
+        match mark {
+            Mark::Highlight => self.highlight = meaning,
+            Mark::Shading => self.shading = meaning,
+            Mark::Strike => self.strike = meaning,
+        }
+    }
+
+    /// Reads the marks of the text of a cell. The text of removing marks is
+    /// cut out, the marks that mean something are dropped from the
+    /// formatting of the rest. Gives the text left and the state of the
+    /// value.
+    pub(crate) fn apply(&self, text: &RichText) -> (RichText, FieldState) {
+        let mut kept = RichText::default();
+        let mut removed = Vec::new();
+        let mut state = FieldState::default();
+
+        for span in text.spans() {
+            let meanings: Vec<_> = [
+                (span.style.highlight, self.highlight),
+                (span.style.shading, self.shading),
+                (span.style.strike, self.strike),
+            ]
+            .iter()
+            .filter(|(on, _)| *on)
+            .map(|(_, meaning)| *meaning)
+            .collect();
+
+            if meanings.contains(&MarkMeaning::Remove) {
+                removed.push(span.text.as_str());
+                continue;
+            }
+
+            if meanings.contains(&MarkMeaning::Verify) && !span.text.trim().is_empty() {
+                state.to_verify = true;
+            }
+
+            let style = Style {
+                highlight: span.style.highlight && self.highlight == MarkMeaning::Ignore,
+                shading: span.style.shading && self.shading == MarkMeaning::Ignore,
+                strike: span.style.strike && self.strike == MarkMeaning::Ignore,
+                ..span.style
+            };
+
+            kept.push(style, &span.text);
+        }
+
+        let removed = removed
+            .concat()
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        state.removed = Some(removed).filter(|removed| !removed.is_empty());
+
+        (kept, state)
+    }
+}
+
+#[test]
+fn marks() {
+    let struck = Style {
+        strike: true,
+        ..Style::default()
+    };
+    let highlighted = Style {
+        highlight: true,
+        italic: true,
+        ..Style::default()
+    };
+
+    let mut text = RichText::plain("Carta ");
+    text.push(struck, "antiga ");
+    text.push(highlighted, "Diário");
+
+    let (kept, state) = AnnotationRules::default().apply(&text);
+
+    assert_eq!(
+        kept.spans().iter().map(|s| s.text.as_str()).collect::<Vec<_>>(),
+        ["Carta ", "Diário"]
+    );
+    assert!(kept.spans()[1].style.italic && !kept.spans()[1].style.highlight);
+    assert!(state.to_verify);
+    assert_eq!(state.removed.as_deref(), Some("antiga"));
+
+    let mut rules = AnnotationRules::default();
+    rules.set(Mark::Strike, MarkMeaning::Ignore);
+    rules.set(Mark::Highlight, MarkMeaning::Ignore);
+
+    let (kept, state) = rules.apply(&text);
+
+    assert_eq!(kept, text);
+    assert!(state.is_clear());
+}
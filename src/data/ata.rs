@@ -2,6 +2,8 @@ use chrono::{Datelike, NaiveDate};
 use lazy_static::lazy_static;
 use never::Never;
 use regex::{Captures, Regex};
+use serde::{ser::SerializeStruct, Serialize, Serializer};
+use super::plain::{Plain, PlainText};
 use std::{
     fmt::{Debug, Display},
     hint::unreachable_unchecked,
@@ -19,7 +21,7 @@ impl FromStr for Session {
     type Err = Never;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s.as_ref() {
+        match s {
             "extraordinária" => Ok(Self::Extraordinary),
             "ordinária" => Ok(Self::Ordinary),
             s => Ok(Self::Other(s.to_owned())),
@@ -37,6 +39,26 @@ impl AsRef<str> for Session {
     }
 }
 
+impl Plain for Session {
+    fn fmt_plain(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Session::Ordinary => f.write_str("ordinária"),
+            Session::Extraordinary => f.write_str("extraordinária"),
+            Session::Other(s) => f.write_str(s),
+        }
+    }
+}
+
+impl Serialize for Session {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Session::Ordinary => serializer.serialize_str("ordinary"),
+            Session::Extraordinary => serializer.serialize_str("extraordinary"),
+            Session::Other(s) => serializer.serialize_str(s),
+        }
+    }
+}
+
 impl Display for Session {
     #[inline]
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -56,7 +78,7 @@ struct NaiveYearMonth(NaiveDate);
 
 impl NaiveYearMonth {
     fn from_ym_opt(year: i32, month: u32) -> Option<Self> {
-        NaiveDate::from_ymd_opt(year, month, 1).map(|d| NaiveYearMonth(d))
+        NaiveDate::from_ymd_opt(year, month, 1).map(NaiveYearMonth)
     }
 
     fn year(&self) -> i32 {
@@ -76,8 +98,8 @@ enum HistoricalData {
 impl HistoricalData {
     fn from_ymd_opt(year: i32, month: u32, day: Option<u32>) -> Option<Self> {
         match day {
-            Some(day) => NaiveDate::from_ymd_opt(year, month, day).map(|d| Self::PreciseDay(d)),
-            None => NaiveYearMonth::from_ym_opt(year, month).map(|d| Self::PreciseMonth(d)),
+            Some(day) => NaiveDate::from_ymd_opt(year, month, day).map(Self::PreciseDay),
+            None => NaiveYearMonth::from_ym_opt(year, month).map(Self::PreciseMonth),
         }
     }
 }
@@ -103,6 +125,42 @@ unsafe fn month(m: u32) -> &'static str {
     }
 }
 
+/// Full name of the month of a date, numbered from 1 as chrono gives it.
+fn month_name(m: u32) -> &'static str {
+    match m {
+        1 => "janeiro",
+        2 => "fevereiro",
+        3 => "março",
+        4 => "abril",
+        5 => "maio",
+        6 => "junho",
+        7 => "julho",
+        8 => "agosto",
+        9 => "setembro",
+        10 => "outubro",
+        11 => "novembro",
+        12 => "dezembro",
+        _ => unreachable!("month {} of a date", m),
+    }
+}
+
+impl Plain for HistoricalData {
+    fn fmt_plain(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HistoricalData::PreciseMonth(d) => {
+                write!(f, "{} de {}", month_name(d.month()), d.year())
+            }
+            HistoricalData::PreciseDay(d) => write!(
+                f,
+                "{} de {} de {}",
+                d.day(),
+                month_name(d.month()),
+                d.year()
+            ),
+        }
+    }
+}
+
 impl Display for HistoricalData {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -122,7 +180,7 @@ impl Display for HistoricalData {
 
 impl Debug for HistoricalData {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        <Self as Display>::fmt(&self, f)
+        <Self as Display>::fmt(self, f)
     }
 }
 
@@ -158,7 +216,7 @@ impl Ata {
             .get(2)
             .map(|m| m.as_str().parse().unwrap())
             .unwrap_or(Session::Ordinary);
-        let day = captures.get(3).map(|m| m.as_str().parse().ok()).flatten();
+        let day = captures.get(3).and_then(|m| m.as_str().parse().ok());
         let month = captures.get(4)?.as_str();
         let year = captures.get(5)?.as_str().parse().ok()?;
 
@@ -231,6 +289,35 @@ impl Display for Ata {
     }
 }
 
+impl Plain for Ata {
+    fn fmt_plain(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.session {
+            Session::Ordinary => write!(f, "{}ª", self.number)?,
+            _ => write!(f, "{}ª sessão {}", self.number, PlainText(&self.session))?,
+        }
+
+        write!(f, " de {}", PlainText(&self.date))
+    }
+}
+
+/// The date is written in ISO 8601, with the month alone when the day was not
+/// recorded, and `precision` tells which one it is.
+impl Serialize for Ata {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let (date, precision) = match &self.date {
+            HistoricalData::PreciseMonth(d) => (d.0.format("%Y-%m").to_string(), "month"),
+            HistoricalData::PreciseDay(d) => (d.format("%Y-%m-%d").to_string(), "day"),
+        };
+
+        let mut ata = serializer.serialize_struct("Ata", 4)?;
+        ata.serialize_field("number", &self.number)?;
+        ata.serialize_field("session", &self.session)?;
+        ata.serialize_field("date", &date)?;
+        ata.serialize_field("precision", precision)?;
+        ata.end()
+    }
+}
+
 impl Debug for Ata {
     #[inline]
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -343,3 +430,21 @@ fn historical_date_order() {
             < HistoricalData::PreciseDay(NaiveDate::from_ymd_opt(1970, 1, 1).unwrap())
     )
 }
+
+#[test]
+fn plain_round_trip() {
+    let cases = [
+        "5º sessão de 17 de agosto de 1902",
+        "6º sessão extraordinária de 26  de junho de 1904",
+        "6º sessão da Assembléa Geral de 22 de Outubro de 1905",
+        "145ª de Novembro de 1909",
+        "30º sessão de 18 outubro de 1903",
+    ];
+
+    for case in cases.iter() {
+        let ata: Ata = case.parse().unwrap();
+        let plain = PlainText(&ata).to_string();
+
+        assert_eq!(plain.parse(), Ok(ata), "{}", plain);
+    }
+}
@@ -0,0 +1,13 @@
+use serde::Serialize;
+
+/// A comment a reviewer left on the document (a Word comment), attached to
+/// the field of the cell it was anchored in.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Comment {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    /// Date of the comment as recorded, `2024-03-01T10:00:00Z`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date: Option<String>,
+    pub text: String,
+}
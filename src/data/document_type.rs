@@ -6,8 +6,13 @@ use std::{
 use lazy_static::lazy_static;
 use never::Never;
 use regex::Regex;
+use serde::{Serialize, Serializer};
 
-use super::{Field, index::Index};
+use super::{
+    index::Index,
+    plain::Plain,
+    Field,
+};
 
 #[derive(PartialEq, Eq, Hash)]
 pub enum DocumentType {
@@ -109,7 +114,54 @@ impl Display for DocumentType {
 impl Debug for DocumentType {
     #[inline]
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        <Self as Display>::fmt(&self, f)
+        <Self as Display>::fmt(self, f)
+    }
+}
+
+impl Plain for DocumentType {
+    fn fmt_plain(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            DocumentType::Almanaque => "almanaque",
+            DocumentType::Anais => "anais",
+            DocumentType::Anuario => "anuário",
+            DocumentType::Artigo => "artigo",
+            DocumentType::Ata => "ata",
+            DocumentType::Atlas => "atlas",
+            DocumentType::Biografia => "biografia",
+            DocumentType::Boletim => "boletim",
+            DocumentType::Carta => "carta",
+            DocumentType::CartaTopografica => "carta topográfica",
+            DocumentType::Catalogo => "catálogo",
+            DocumentType::Conferencia => "conferência",
+            DocumentType::Dicionario => "dicionário",
+            DocumentType::Discurso => "discurso",
+            DocumentType::DocumentoJuridico => "documento jurídico",
+            DocumentType::DocumentoOficial => "documento oficial",
+            DocumentType::Estatuto => "estatuto",
+            DocumentType::Folheto => "folheto",
+            DocumentType::Jornal => "jornal",
+            DocumentType::Literatura => "literatura",
+            DocumentType::Livro => "livro",
+            DocumentType::Manifesto => "manifesto",
+            DocumentType::Manuscristo => "manuscrito",
+            DocumentType::Mapa => "mapa",
+            DocumentType::Medalha => "medalha",
+            DocumentType::Memorias => "memórias",
+            DocumentType::Moeda => "moeda",
+            DocumentType::Obra => "obra",
+            DocumentType::Periodico => "periódico",
+            DocumentType::Poesia => "poesias",
+            DocumentType::Relatorio => "relatório",
+            DocumentType::Revista => "revista",
+            DocumentType::Tese => "tese",
+            DocumentType::Other(s) => s,
+        })
+    }
+}
+
+impl Serialize for DocumentType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
     }
 }
 
@@ -128,7 +180,7 @@ impl Ord for DocumentType {
 }
 
 impl DocumentType {
-    fn index(&self) -> Index {
+    fn index(&self) -> Index<'_> {
         self.as_ref().into()
     }
 }
@@ -180,14 +232,15 @@ impl FromStr for DocumentType {
     }
 }
 
-#[derive(PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+#[serde(transparent)]
 pub struct DocumentTypes {
     types: Vec<Field<DocumentType>>,
 }
 
-impl Into<Vec<Field<DocumentType>>> for DocumentTypes {
-    fn into(self) -> Vec<Field<DocumentType>> {
-        self.types
+impl From<DocumentTypes> for Vec<Field<DocumentType>> {
+    fn from(types: DocumentTypes) -> Self {
+        types.types
     }
 }
 
@@ -207,7 +260,7 @@ impl Display for DocumentTypes {
             }
         }
 
-        for t in iter.next() {
+        if let Some(t) = iter.next() {
             kind_and_index(t, f)?;
         }
 
@@ -220,10 +273,28 @@ impl Display for DocumentTypes {
     }
 }
 
+impl Plain for DocumentTypes {
+    fn fmt_plain(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, t) in self.types.iter().enumerate() {
+            if i > 0 {
+                f.write_str("/")?;
+            }
+
+            match t {
+                // A lone question mark reads back as an absent type.
+                Field::Absent => f.write_str("?")?,
+                t => t.fmt_plain(f)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
 impl Debug for DocumentTypes {
     #[inline]
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        <Self as Display>::fmt(&self, f)
+        <Self as Display>::fmt(self, f)
     }
 }
 
@@ -284,3 +355,15 @@ fn parse_string() {
         }
     );
 }
+
+#[test]
+fn plain_round_trip() {
+    use super::PlainText;
+
+    for case in ["periódico  (drama)", "boletim / documentos oficiais", "boletim ( a conferir )", "poesias/documento jurídico"].iter() {
+        let types: DocumentTypes = case.parse().unwrap();
+        let plain = PlainText(&types).to_string();
+
+        assert_eq!(plain.parse::<DocumentTypes>().unwrap(), types, "{}", plain);
+    }
+}
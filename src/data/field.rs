@@ -1,6 +1,7 @@
 use std::{cmp::Ordering, fmt::{Debug, Display}, mem::swap, str::FromStr};
 
 use never::Never;
+use serde::{ser::SerializeMap, Serialize, Serializer};
 
 use super::{Ata, NumberVolume, Range, index::Index, page::Page};
 
@@ -22,7 +23,7 @@ impl<T: AsRef<str>> AsRef<str> for Field<T> {
 }
 
 impl<T: AsRef<str>> Field<T> {
-    pub fn index(&self) -> Index {
+    pub fn index(&self) -> Index<'_> {
         self.as_ref().into()
     }
 }
@@ -183,3 +184,21 @@ impl<T: Display> Display for Field<T> {
         }
     }
 }
+
+/// Absent values are `null` and values that could not be understood are
+/// kept as typed: `{"raw": "...", "not_standard": true}`.
+impl<T: Serialize> Serialize for Field<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Field::Absent => serializer.serialize_none(),
+            Field::Present(t) => t.serialize(serializer),
+            Field::NotStandard(s) => {
+                let mut map = serializer.serialize_map(Some(2))?;
+                map.serialize_entry("raw", s)?;
+                map.serialize_entry("not_standard", &true)?;
+                map.end()
+            }
+        }
+    }
+}
+
@@ -0,0 +1,32 @@
+use serde::Serialize;
+
+use super::rich_text::is_false;
+
+/// Editorial state of a value, given by the marks typed on its text (see
+/// [`AnnotationRules`](crate::AnnotationRules)).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct FieldState {
+    /// The value is doubtful and must be checked against the original.
+    #[serde(skip_serializing_if = "is_false")]
+    pub to_verify: bool,
+    /// Text struck out of the value, which was left out of it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub removed: Option<String>,
+}
+
+impl FieldState {
+    /// Whether the value has no editorial mark.
+    pub fn is_clear(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Adds the state of text added to the value.
+    pub fn merge(&mut self, other: FieldState) {
+        self.to_verify |= other.to_verify;
+
+        self.removed = match (self.removed.take(), other.removed) {
+            (Some(removed), Some(other)) => Some(format!("{}; {}", removed, other)),
+            (removed, other) => removed.or(other),
+        };
+    }
+}
@@ -1,145 +1,940 @@
-use std::fmt::Display;
-use std::str::FromStr;
+use std::fmt;
+use std::rc::Rc;
+
+use serde::ser::{Serialize, SerializeMap, SerializeSeq, Serializer};
 
 pub use self::ata::Ata;
+pub use self::comment::Comment;
 pub use self::document_type::{DocumentType, DocumentTypes};
 pub use self::field::Field;
+pub use self::field_state::FieldState;
+pub use self::note::{Note, NoteKind};
 pub use self::number_volume::NumberVolume;
-use self::page::Page;
+pub use self::page::Page;
+pub use self::plain::{Plain, PlainText};
+pub use self::provenance::Provenance;
 pub use self::range::Range;
+pub use self::rich_text::{RichText, Span, Style};
+pub use self::schema::{normalize_key, FieldSpec, Schema, SchemaError, ValueType};
+pub use self::sub_table::SubTable;
+pub use self::value::Value;
+
+pub(crate) use self::index::Index;
 
 mod range;
 
 mod ata;
+mod comment;
 mod document_type;
 mod field;
+mod field_state;
+mod note;
 mod number_volume;
 mod page;
+mod plain;
+mod provenance;
+mod rich_text;
+mod schema;
+mod sub_table;
+mod value;
 
 mod index;
 
-#[derive(PartialEq, Eq, PartialOrd, Ord)]
+/// Why a field could not be stored in a [`Row`], or was stored with a
+/// caveat. The key and value are given back to the caller.
+#[derive(Debug, PartialEq, Eq)]
+pub enum SetFieldError {
+    /// The row already has a value for this field, the value was not stored.
+    Occupied(String, String),
+    /// The value could not be read in the form of its field. It was stored
+    /// all the same, kept as typed.
+    NotStandard(String, String),
+    /// The value has no key, or a key of punctuation alone, and names no
+    /// field. It was not stored.
+    NoKey(String),
+}
+
+/// One record of the catalog: a value for some of the fields of its
+/// [`Schema`], plus the keys the schema does not know with their text.
+///
+/// Values read from documents keep their [`Provenance`].
+///
+/// A key typed in several rows of a table (a merged key cell) has several
+/// values, see [`Row::repeated`].
+///
+/// Rows compare field by field in schema order, then by their extra fields;
+/// provenance and table numbers are not compared. Fields the row does not
+/// have are left out of the serialized row, a key with several values is
+/// serialized as the list of them, extra fields are serialized under
+/// `extra`, formatted text under `rich_text`, editorial states under
+/// `states`, reviewer comments under `comments`, footnotes and endnotes under
+/// `notes`, the tables typed inside cells under `tables` and the provenance
+/// of every value under `provenance`.
 pub struct Row {
-    pub year: Option<Field<Range>>,                 // Ano da Revista: 1903
-    pub number_volume: Option<Field<NumberVolume>>, // Número/volume da revista: N.2 V.2
-    pub ata: Option<Field<Ata>>, // Número e data da Ata de sessão: 5ª sessão de 17 de agosto de 1902
-    pub page: Option<Field<Page>>, // Página: p.138
-    pub kind: Option<DocumentTypes>, // Tipo de documento: Revista
-    pub title: Option<Field<String>>, // Título completo do manuscrito: Revista do IAGP
-    pub doner: Option<Field<String>>, // Quem doou?: 1º secretário do IAGP
-    pub time: Option<Field<String>>, // Qual a temporalidade do documento: Não mencionado
+    schema: Rc<Schema>,
+    values: Vec<Option<Value>>,
+    sources: Vec<Option<Provenance>>,
+    extras: Vec<(String, String)>,
+    extra_sources: Vec<Option<Provenance>>,
+    /// Values after the first of a key typed in several rows, by field name
+    /// or extra key.
+    repeated: Vec<(String, Vec<Value>)>,
+    /// Formatted text of values, by field name or extra key.
+    rich: Vec<(String, RichText)>,
+    /// Editorial states of values, by field name or extra key.
+    states: Vec<(String, FieldState)>,
+    /// Reviewer comments, by field name or extra key, in document order.
+    comments: Vec<(String, Comment)>,
+    /// Footnotes and endnotes, by field name or extra key, in document order.
+    notes: Vec<(String, Note)>,
+    /// Tables typed inside cells, by field name or extra key.
+    tables: Vec<(String, SubTable)>,
+    table_number: Option<u32>,
+}
+
+impl Default for Row {
+    /// An empty row of the default schema.
+    fn default() -> Self {
+        Self::new(Rc::new(Schema::default()))
+    }
 }
 
 impl Row {
-    pub fn new() -> Self {
+    pub fn new(schema: Rc<Schema>) -> Self {
+        let values = schema.fields().iter().map(|_| None).collect();
+        let sources = schema.fields().iter().map(|_| None).collect();
+
         Self {
-            year: None,
-            number_volume: None,
-            ata: None,
-            page: None,
-            kind: None,
-            title: None,
-            doner: None,
-            time: None,
+            schema,
+            values,
+            sources,
+            extras: Vec::new(),
+            extra_sources: Vec::new(),
+            repeated: Vec::new(),
+            rich: Vec::new(),
+            states: Vec::new(),
+            comments: Vec::new(),
+            notes: Vec::new(),
+            tables: Vec::new(),
+            table_number: None,
+        }
+    }
+
+    #[inline]
+    pub fn schema(&self) -> &Rc<Schema> {
+        &self.schema
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.iter().all(Option::is_none) && self.extras.is_empty()
+    }
+
+    /// Value of the field called `name`.
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        self.schema
+            .position(name)
+            .and_then(|i| self.values[i].as_ref())
+    }
+
+    /// Ano da Revista, with the default schema.
+    pub fn year(&self) -> Option<&Field<Range>> {
+        match self.get("year") {
+            Some(Value::Range(year)) => Some(year),
+            _ => None,
+        }
+    }
+
+    /// Número/volume da revista, with the default schema.
+    pub fn number_volume(&self) -> Option<&Field<NumberVolume>> {
+        match self.get("number_volume") {
+            Some(Value::NumberVolume(number_volume)) => Some(number_volume),
+            _ => None,
+        }
+    }
+
+    /// Número e data da Ata de sessão, with the default schema.
+    pub fn ata(&self) -> Option<&Field<Ata>> {
+        match self.get("ata") {
+            Some(Value::Ata(ata)) => Some(ata),
+            _ => None,
+        }
+    }
+
+    /// Página, with the default schema.
+    pub fn page(&self) -> Option<&Field<Page>> {
+        match self.get("page") {
+            Some(Value::Page(page)) => Some(page),
+            _ => None,
+        }
+    }
+
+    /// Tipo de documento, with the default schema.
+    pub fn kind(&self) -> Option<&DocumentTypes> {
+        match self.get("kind") {
+            Some(Value::DocumentTypes(kind)) => Some(kind),
+            _ => None,
         }
     }
 
-    pub fn set_field(&mut self, field: (String, String)) -> Result<(), (String, String)> {
+    /// Título completo do manuscrito, with the default schema.
+    #[inline]
+    pub fn title(&self) -> Option<&Field<String>> {
+        self.get("title").and_then(Value::as_text)
+    }
+
+    /// Quem doou?, with the default schema.
+    #[inline]
+    pub fn doner(&self) -> Option<&Field<String>> {
+        self.get("doner").and_then(Value::as_text)
+    }
+
+    /// Qual a temporalidade do documento, with the default schema.
+    #[inline]
+    pub fn time(&self) -> Option<&Field<String>> {
+        self.get("time").and_then(Value::as_text)
+    }
+
+    /// The fields the row has, in schema order.
+    pub fn fields(&self) -> impl Iterator<Item = (&FieldSpec, &Value)> {
+        self.schema
+            .fields()
+            .iter()
+            .zip(self.values.iter())
+            .filter_map(|(spec, value)| value.as_ref().map(|v| (spec, v)))
+    }
+
+    /// Keys the schema does not know, with their values, in the order they
+    /// were set. Keys are kept as typed in the table.
+    #[inline]
+    pub fn extras(&self) -> &[(String, String)] {
+        &self.extras
+    }
+
+    /// The values of a field or extra key after the first one, when its key
+    /// was typed in several rows (a merged key cell). Each is read in the
+    /// form of the field on its own.
+    pub fn repeated(&self, name: &str) -> &[Value] {
+        self.repeated
+            .iter()
+            .find(|(n, _)| n == name)
+            .map_or(&[], |(_, values)| values)
+    }
+
+    /// The formatted text of the value of a field or extra key, when it has
+    /// any formatting.
+    pub fn rich_text(&self, name: &str) -> Option<&RichText> {
+        self.rich
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, text)| text)
+    }
+
+    /// The editorial state of the value of a field or extra key, when it has
+    /// any.
+    pub fn state(&self, name: &str) -> Option<&FieldState> {
+        self.states
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, state)| state)
+    }
+
+    /// Reviewer comments on the row, by field name or extra key, in document
+    /// order.
+    #[inline]
+    pub fn comments(&self) -> &[(String, Comment)] {
+        &self.comments
+    }
+
+    /// The comments on the value of a field or extra key.
+    pub fn field_comments<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Comment> {
+        self.comments
+            .iter()
+            .filter(move |(n, _)| n == name)
+            .map(|(_, comment)| comment)
+    }
+
+    /// Footnotes and endnotes of the row, by field name or extra key, in
+    /// document order.
+    #[inline]
+    pub fn notes(&self) -> &[(String, Note)] {
+        &self.notes
+    }
+
+    /// The footnotes and endnotes on the value of a field or extra key.
+    pub fn field_notes<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Note> {
+        self.notes
+            .iter()
+            .filter(move |(n, _)| n == name)
+            .map(|(_, note)| note)
+    }
+
+    /// Tables typed inside the cells of the row, by field name or extra key,
+    /// in the order they were read.
+    #[inline]
+    pub fn sub_tables(&self) -> &[(String, SubTable)] {
+        &self.tables
+    }
+
+    /// The table typed inside the cell of a field or extra key.
+    pub fn sub_table(&self, name: &str) -> Option<&SubTable> {
+        self.tables
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, table)| table)
+    }
+
+    /// Number of the caption of the table the row was read from ("Tabela
+    /// 57"), the number editors use to refer to the record.
+    #[inline]
+    pub fn table_number(&self) -> Option<u32> {
+        self.table_number
+    }
+
+    #[inline]
+    pub fn set_table_number(&mut self, number: Option<u32>) {
+        self.table_number = number;
+    }
+
+    /// Where the value of the field or extra key called `name` was read.
+    pub fn provenance(&self, name: &str) -> Option<&Provenance> {
+        match self.schema.position(name) {
+            Some(i) => self.sources[i].as_ref(),
+            None => self
+                .extras
+                .iter()
+                .position(|(key, _)| key == name)
+                .and_then(|i| self.extra_sources[i].as_ref()),
+        }
+    }
+
+    /// Where the values of the row were read, fields in schema order then
+    /// extra fields.
+    pub fn provenances(&self) -> impl Iterator<Item = &Provenance> {
+        self.sources
+            .iter()
+            .chain(self.extra_sources.iter())
+            .filter_map(Option::as_ref)
+    }
+
+    /// The fields as plain text, in schema order, the values of a key typed
+    /// in several rows joined with "; ". Setting each value back with the
+    /// key of its field rebuilds the same row, but for those.
+    pub fn plain_values(&self) -> Vec<Option<String>> {
+        self.schema
+            .fields()
+            .iter()
+            .zip(self.values.iter())
+            .map(|(spec, v)| {
+                v.as_ref()
+                    .map(|v| self.joined(&spec.name, PlainText(v).to_string()))
+            })
+            .collect()
+    }
+
+    /// The plain text of the first value of a field or extra key followed by
+    /// its repeated values, joined with "; ".
+    pub fn joined(&self, name: &str, first: String) -> String {
+        self.repeated(name).iter().fold(first, |mut text, value| {
+            text.push_str("; ");
+            text.push_str(&PlainText(value).to_string());
+            text
+        })
+    }
+
+    /// Year used to group the row in the catalog: the year given by the first
+    /// section field (see [`FieldSpec::section`]) that has one. For the
+    /// periodicals that is the year of the periodical when known, otherwise
+    /// the year of the session that received it.
+    pub fn section_year(&self) -> Option<Range> {
+        self.schema
+            .fields()
+            .iter()
+            .zip(self.values.iter())
+            .filter(|(spec, _)| spec.section)
+            .find_map(|(_, value)| value.as_ref().and_then(Value::year))
+    }
+
+    /// Stores the value of a table key. A key that names no field of the
+    /// schema is kept as an extra field, a value without key is not stored.
+    #[inline]
+    pub fn set_field(&mut self, field: (String, String)) -> Result<(), SetFieldError> {
+        self.set_field_from(field, None)
+    }
+
+    /// Like [`set_field`](Self::set_field), recording where the value was
+    /// read.
+    pub fn set_field_from(
+        &mut self,
+        field: (String, String),
+        provenance: Option<Provenance>,
+    ) -> Result<(), SetFieldError> {
         let (key, value) = field;
 
-        let key = key.trim().to_lowercase();
+        let key = key.trim().to_owned();
         let value = value.trim().to_owned();
 
-        fn set_field_internal<F: FromStr>(
-            field: &mut Option<F>,
-            key: String,
-            value: String,
-        ) -> Result<(), (String, String)> {
-            match field {
-                None => match value.as_str().parse() {
-                    Ok(f) => {
-                        field.replace(f);
-                        Ok(())
+        if normalize_key(&key).is_empty() {
+            if value.is_empty() {
+                return Ok(());
+            }
+
+            return Err(SetFieldError::NoKey(value));
+        }
+
+        let i = match self.schema.classify(&key) {
+            Some(i) => i,
+            None => return self.set_extra(key, value, provenance),
+        };
+
+        if self.values[i].is_some() {
+            return Err(SetFieldError::Occupied(key, value));
+        }
+
+        let parsed = Value::parse(self.schema.fields()[i].kind, &value);
+        let not_standard = parsed.is_not_standard();
+
+        self.values[i] = Some(parsed);
+        self.sources[i] = provenance;
+
+        if not_standard {
+            return Err(SetFieldError::NotStandard(key, value));
+        }
+
+        Ok(())
+    }
+
+    /// Adds text to the value of a key already set, read from a cell that
+    /// continues it (a row spanning the table). The text is joined to the
+    /// plain form of the last value of the key with `separator` and the
+    /// whole is parsed again. A key not yet set is set to the text.
+    pub fn extend_field(&mut self, key: &str, separator: &str, text: &RichText) {
+        let key = key.trim();
+        let rich = text.clone().trim();
+        let text = PlainText(&rich).to_string();
+
+        if text.is_empty() {
+            return;
+        }
+
+        let previous = self.joined_value(key);
+        let name = self.entry_name(key);
+        let kind = self.value_type(key);
+
+        // After repeated values, the text continues the last of them.
+        if let Some((_, values)) = self.repeated.iter_mut().find(|(n, _)| *n == name) {
+            let last = values.last_mut().expect("repeated values are never empty");
+            let value = format!("{}{}{}", PlainText(&*last), separator, text);
+
+            *last = Value::parse(kind, &value);
+
+            if let Some(source) = self.source_mut(key) {
+                source.raw.push_str(separator);
+                source.raw.push_str(&text);
+            }
+
+            self.join_rich(key, separator, rich, previous);
+            return;
+        }
+
+        let extend = |value: &str, source: Option<&mut Provenance>| {
+            if let Some(source) = source {
+                source.raw.push_str(separator);
+                source.raw.push_str(&text);
+            }
+
+            if value.is_empty() {
+                text.clone()
+            } else {
+                format!("{}{}{}", value, separator, text)
+            }
+        };
+
+        match self.schema.classify(key) {
+            Some(i) => {
+                let value = match self.values[i].as_ref() {
+                    Some(value) => extend(&PlainText(value).to_string(), self.sources[i].as_mut()),
+                    None => text.clone(),
+                };
+
+                self.values[i] = Some(Value::parse(self.schema.fields()[i].kind, &value));
+            }
+            None => {
+                let normalized = normalize_key(key);
+
+                let position = self
+                    .extras
+                    .iter()
+                    .position(|(k, _)| normalize_key(k) == normalized);
+
+                match position {
+                    Some(i) => {
+                        self.extras[i].1 = extend(&self.extras[i].1, self.extra_sources[i].as_mut())
                     }
-                    _ => Err((key, value)),
-                },
-                Some(_) => Err((key, value)),
+                    None => {
+                        self.extras.push((key.to_owned(), text.clone()));
+                        self.extra_sources.push(None);
+                    }
+                }
             }
         }
 
-        if key.starts_with("ano") {
-            set_field_internal(&mut self.year, key, value)
-        } else if key.contains("ata") {
-            set_field_internal(&mut self.ata, key, value)
-        } else if key.starts_with("numero") || key.starts_with("número") {
-            set_field_internal(&mut self.number_volume, key, value)
-        } else if key.starts_with("página") || key.starts_with("pagina") {
-            set_field_internal(&mut self.page, key, value)
-        } else if key.starts_with("tipo") {
-            set_field_internal(&mut self.kind, key, value.to_lowercase())
-        } else if key.starts_with("título") || key.starts_with("titulo") {
-            set_field_internal(&mut self.title, key, value.replace("$", r"\$"))
-        } else if key.starts_with("quem") {
-            set_field_internal(&mut self.doner, key, value.replace("°", "º"))
-        } else if key.starts_with("qual") {
-            set_field_internal(&mut self.time, key, value)
-        } else if !key.is_empty() || !value.is_empty() {
-            eprintln!("Warning: unhandled key {} with value {}", key, value);
-            eprintln!("Row current state {}", self);
-            Ok(())
-        } else {
-            Ok(())
+        self.join_rich(key, separator, rich, previous);
+    }
+
+    /// Adds a value to a key typed in several rows, read from the cell next
+    /// to a merged key cell. The value is read in the form of the field on
+    /// its own and kept after the ones before it (see
+    /// [`repeated`](Self::repeated)). A key not yet set is set to the value.
+    pub fn add_value(&mut self, key: &str, text: &RichText) {
+        let key = key.trim();
+
+        if !self.has(key) {
+            self.extend_field(key, "; ", text);
+            return;
         }
+
+        let rich = text.clone().trim();
+        let text = PlainText(&rich).to_string();
+
+        if text.is_empty() {
+            return;
+        }
+
+        let previous = self.joined_value(key);
+        let name = self.entry_name(key);
+        let value = Value::parse(self.value_type(key), &text);
+
+        match self.repeated.iter_mut().find(|(n, _)| *n == name) {
+            Some((_, values)) => values.push(value),
+            None => self.repeated.push((name, vec![value])),
+        }
+
+        if let Some(source) = self.source_mut(key) {
+            source.raw.push_str("; ");
+            source.raw.push_str(&text);
+        }
+
+        self.join_rich(key, "; ", rich, previous);
     }
-}
 
-impl Display for Row {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        writeln!(f, r"\begin{{tabularx}}{{\linewidth}}{{| p{{.4545\linewidth}} | p{{.4625\linewidth}} |}}")?;
-        writeln!(f, r"    \hline")?;
+    /// Joins formatted text to the one of a key whose value was added to,
+    /// `previous` being the plain text of the key before. The formatting of
+    /// both parts is kept when either has some.
+    fn join_rich(&mut self, key: &str, separator: &str, rich: RichText, previous: Option<String>) {
+        let name = self.entry_name(key);
+        let stored = self.rich.iter().position(|(n, _)| *n == name);
+
+        if stored.is_none() && rich.is_plain() {
+            return;
+        }
+
+        let mut joined = match stored {
+            Some(i) => self.rich.remove(i).1,
+            None => RichText::plain(previous.as_deref().unwrap_or("")),
+        };
+
+        if !joined.is_empty() {
+            joined.push(Style::default(), separator);
+        }
+
+        joined.extend(rich);
+        self.set_rich_text(key, joined);
+    }
+
+    /// Keeps the formatted text of the value of a text field or an extra
+    /// key. Text without formatting is not kept, the plain value is enough.
+    pub fn set_rich_text(&mut self, key: &str, text: RichText) {
+        let kind = self
+            .schema
+            .classify(key)
+            .map(|i| self.schema.fields()[i].kind);
+
+        if kind.is_some_and(|kind| kind != ValueType::Text) {
+            return;
+        }
+
+        let mut text = text.trim();
 
-        if let Some(year) = self.year.as_ref() {
-            writeln!(f, r"    \hfill Ano da revista & {} \\", year)?;
-            writeln!(f, r"    \hline")?;
+        // As `Value::parse` does for text fields.
+        if kind.is_some() {
+            text.replace("°", "º");
         }
 
-        if let Some(nv) = self.number_volume.as_ref() {
-            writeln!(f, r"    \hfill Número/volume da revista & {} \\", nv)?;
-            writeln!(f, r"    \hline")?;
+        let name = self.entry_name(key);
+        self.rich.retain(|(n, _)| *n != name);
+
+        if !text.is_plain() {
+            self.rich.push((name, text));
         }
+    }
 
-        if let Some(ata) = self.ata.as_ref() {
-            writeln!(f, r"    \hfill Nº e data da ata de sessão & {} \\", ata)?;
-            writeln!(f, r"    \hline")?;
+    /// Adds to the editorial state of the value of a key, as read from the
+    /// marks of a cell.
+    pub fn annotate(&mut self, key: &str, state: FieldState) {
+        if state.is_clear() {
+            return;
         }
 
-        if let Some(pages) = self.page.as_ref() {
-            writeln!(f, r"    \hfill Página(s) & {} \\", pages)?;
-            writeln!(f, r"    \hline")?;
+        let name = self.entry_name(key);
+
+        match self.states.iter_mut().find(|(n, _)| *n == name) {
+            Some((_, existing)) => existing.merge(state),
+            None => self.states.push((name, state)),
         }
+    }
+
+    /// Attaches a comment anchored in the row of a key.
+    pub fn add_comment(&mut self, key: &str, comment: Comment) {
+        let name = self.entry_name(key);
+        self.comments.push((name, comment));
+    }
 
-        if let Some(kind) = self.kind.as_ref() {
-            writeln!(f, r"    \hfill Tipo de documento & {} \\", kind)?;
-            writeln!(f, r"    \hline")?;
+    /// Attaches a footnote or endnote typed in the row of a key.
+    pub fn add_note(&mut self, key: &str, note: Note) {
+        let name = self.entry_name(key);
+        self.notes.push((name, note));
+    }
+
+    /// Attaches a table typed inside the value cell of a key. The lines of
+    /// a second table for the same key go after the ones of the first.
+    pub fn add_sub_table(&mut self, key: &str, table: SubTable) {
+        if table.is_empty() {
+            return;
         }
 
-        if let Some(title) = self.title.as_ref() {
-            writeln!(f, r"    \hfill Título completo do documento & {} \\", title)?;
-            writeln!(f, r"    \hline")?;
+        let name = self.entry_name(key);
+
+        match self.tables.iter_mut().find(|(n, _)| *n == name) {
+            Some((_, existing)) => existing.extend(table),
+            None => self.tables.push((name, table)),
         }
+    }
 
-        if let Some(doner) = self.doner.as_ref() {
-            writeln!(f, r"    \hfill Quem doou? & {}{} \\", doner, doner.index())?;
-            writeln!(f, r"    \hline")?;
+    /// The name a key is stored under: the name of its field, or the extra
+    /// key it matches as typed first.
+    fn entry_name(&self, key: &str) -> String {
+        match self.schema.classify(key) {
+            Some(i) => self.schema.fields()[i].name.clone(),
+            None => {
+                let normalized = normalize_key(key);
+
+                self.extras
+                    .iter()
+                    .find(|(k, _)| normalize_key(k) == normalized)
+                    .map_or_else(|| key.trim().to_owned(), |(k, _)| k.clone())
+            }
         }
+    }
+
+    /// Whether the value of a key is set.
+    #[inline]
+    pub fn has(&self, key: &str) -> bool {
+        self.plain_value(key).is_some()
+    }
+
+    /// The plain form of all the values of a key, if it is set.
+    fn joined_value(&self, key: &str) -> Option<String> {
+        let first = self.plain_value(key)?;
+        Some(self.joined(&self.entry_name(key), first))
+    }
+
+    /// Where the first value of a key was read.
+    fn source_mut(&mut self, key: &str) -> Option<&mut Provenance> {
+        match self.schema.classify(key) {
+            Some(i) => self.sources[i].as_mut(),
+            None => {
+                let normalized = normalize_key(key);
 
-        if let Some(time) = self.time.as_ref() {
-            writeln!(f, r"    \hfill Temporalidade do documento & {} \\", time)?;
-            writeln!(f, r"    \hline")?;
+                let i = self
+                    .extras
+                    .iter()
+                    .position(|(k, _)| normalize_key(k) == normalized)?;
+
+                self.extra_sources[i].as_mut()
+            }
         }
+    }
+
+    /// The kind of value of a key, text for an extra key.
+    fn value_type(&self, key: &str) -> ValueType {
+        self.schema
+            .classify(key)
+            .map_or(ValueType::Text, |i| self.schema.fields()[i].kind)
+    }
 
-        write!(f, r"\end{{tabularx}}")?;
+    /// The plain form of the value of a key, if it is set.
+    fn plain_value(&self, key: &str) -> Option<String> {
+        match self.schema.classify(key) {
+            Some(i) => self.values[i].as_ref().map(|v| PlainText(v).to_string()),
+            None => {
+                let normalized = normalize_key(key);
+
+                self.extras
+                    .iter()
+                    .find(|(k, _)| normalize_key(k) == normalized)
+                    .map(|(_, v)| v.clone())
+            }
+        }
+    }
+
+    fn set_extra(
+        &mut self,
+        key: String,
+        value: String,
+        provenance: Option<Provenance>,
+    ) -> Result<(), SetFieldError> {
+        let normalized = normalize_key(&key);
+
+        if self.extras.iter().any(|(k, _)| normalize_key(k) == normalized) {
+            return Err(SetFieldError::Occupied(key, value));
+        }
+
+        self.extras.push((key, value));
+        self.extra_sources.push(provenance);
 
         Ok(())
     }
 }
+
+impl PartialEq for Row {
+    fn eq(&self, other: &Self) -> bool {
+        self.values == other.values
+            && self.extras == other.extras
+            && self.repeated == other.repeated
+    }
+}
+
+impl Eq for Row {}
+
+impl PartialOrd for Row {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Row {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.values
+            .cmp(&other.values)
+            .then_with(|| self.extras.cmp(&other.extras))
+            .then_with(|| self.repeated.cmp(&other.repeated))
+    }
+}
+
+impl fmt::Debug for Row {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(self.fields().map(|(spec, value)| (&spec.name, value)))
+            .entries(self.extras.iter().map(|(k, v)| (k, v)))
+            .finish()
+    }
+}
+
+impl Serialize for Row {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+
+        if let Some(number) = self.table_number {
+            map.serialize_entry("table_number", &number)?;
+        }
+
+        for (spec, value) in self.fields() {
+            map.serialize_entry(&spec.name, &Values(value, self.repeated(&spec.name)))?;
+        }
+
+        if !self.extras.is_empty() {
+            map.serialize_entry("extra", &Extras(self))?;
+        }
+
+        if !self.rich.is_empty() {
+            map.serialize_entry("rich_text", &Entries(&self.rich))?;
+        }
+
+        if !self.states.is_empty() {
+            map.serialize_entry("states", &Entries(&self.states))?;
+        }
+
+        if !self.comments.is_empty() {
+            map.serialize_entry("comments", &ByField(&self.comments))?;
+        }
+
+        if !self.notes.is_empty() {
+            map.serialize_entry("notes", &ByField(&self.notes))?;
+        }
+
+        if !self.tables.is_empty() {
+            map.serialize_entry("tables", &Entries(&self.tables))?;
+        }
+
+        if self.provenances().next().is_some() {
+            map.serialize_entry("provenance", &Provenances(self))?;
+        }
+
+        map.end()
+    }
+}
+
+/// The value of a key, or the list of its values when it has several.
+struct Values<'a, T>(&'a T, &'a [Value]);
+
+impl<'a, T: Serialize> Serialize for Values<'a, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let Values(first, repeated) = self;
+
+        if repeated.is_empty() {
+            return first.serialize(serializer);
+        }
+
+        let mut seq = serializer.serialize_seq(Some(repeated.len() + 1))?;
+
+        seq.serialize_element(first)?;
+        for value in repeated.iter() {
+            seq.serialize_element(value)?;
+        }
+
+        seq.end()
+    }
+}
+
+/// Extra fields serialized as a map from key to value, in row order.
+struct Extras<'a>(&'a Row);
+
+impl<'a> Serialize for Extras<'a> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let row = self.0;
+        let mut map = serializer.serialize_map(Some(row.extras.len()))?;
+
+        for (key, value) in row.extras.iter() {
+            map.serialize_entry(key, &Values(value, row.repeated(key)))?;
+        }
+
+        map.end()
+    }
+}
+
+/// Comments or notes serialized as a list, each naming its field.
+struct ByField<'a, T>(&'a [(String, T)]);
+
+impl<'a, T: Serialize> Serialize for ByField<'a, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(serde::Serialize)]
+        struct OnField<'a, T> {
+            field: &'a str,
+            #[serde(flatten)]
+            item: &'a T,
+        }
+
+        let mut seq = serializer.serialize_seq(Some(self.0.len()))?;
+
+        for (field, item) in self.0 {
+            seq.serialize_element(&OnField { field, item })?;
+        }
+
+        seq.end()
+    }
+}
+
+/// Formatted text, states or sub-tables serialized as a map from key to
+/// value, in row order.
+struct Entries<'a, T>(&'a [(String, T)]);
+
+impl<'a, T: Serialize> Serialize for Entries<'a, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.0.len()))?;
+
+        for (key, value) in self.0 {
+            map.serialize_entry(key, value)?;
+        }
+
+        map.end()
+    }
+}
+
+/// Provenance of the values of a row, by field name then extra key.
+struct Provenances<'a>(&'a Row);
+
+impl<'a> Serialize for Provenances<'a> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let row = self.0;
+        let mut map = serializer.serialize_map(None)?;
+
+        let names = row.schema.fields().iter().map(|f| &f.name);
+        for (name, source) in names.zip(row.sources.iter()) {
+            if let Some(source) = source {
+                map.serialize_entry(name, source)?;
+            }
+        }
+
+        let keys = row.extras.iter().map(|(key, _)| key);
+        for (key, source) in keys.zip(row.extra_sources.iter()) {
+            if let Some(source) = source {
+                map.serialize_entry(key, source)?;
+            }
+        }
+
+        map.end()
+    }
+}
+
+#[test]
+fn set_field_errors() {
+    let field = |key: &str, value: &str| (key.to_owned(), value.to_owned());
+    let mut row = Row::new(Rc::new(Schema::default()));
+
+    assert_eq!(row.set_field(field("Página", "p.7")), Ok(()));
+    assert!(matches!(
+        row.set_field(field("Página", "p.8")),
+        Err(SetFieldError::Occupied(..))
+    ));
+    assert!(matches!(
+        row.set_field(field("Ano da Revista", "por volta de")),
+        Err(SetFieldError::NotStandard(..))
+    ));
+    assert_eq!(
+        row.set_field(field(" : ", "sem título")),
+        Err(SetFieldError::NoKey("sem título".to_owned()))
+    );
+    assert_eq!(row.set_field(field("", "")), Ok(()));
+    assert_eq!(row.get("page").unwrap().to_string(), "p.~7");
+    assert_eq!(row.get("year").unwrap().to_string(), "por volta de");
+    assert!(row.extras().is_empty());
+}
+
+#[test]
+fn repeated_values() {
+    let mut row = Row::new(Rc::new(Schema::default()));
+
+    row.add_value("Ano da Revista", &RichText::plain("1905"));
+    row.add_value("Ano da Revista", &RichText::plain("1906-1907"));
+    row.add_value("Quem doou?", &RichText::plain("Abel"));
+    row.add_value("Quem doou?", &RichText::plain("Tavares"));
+    row.extend_field("Quem doou?", " ", &RichText::plain("de Lyra"));
+    row.add_value("Observação", &RichText::plain("rasgada"));
+    row.add_value("Observação", &RichText::plain("sem capa"));
+
+    // Each value is read on its own, not from the values joined.
+    assert_eq!(row.get("year").unwrap().to_string(), "1905");
+    assert_eq!(
+        row.repeated("year"),
+        [Value::parse(ValueType::Range, "1906-1907")]
+    );
+    assert_eq!(row.repeated("doner")[0].to_string(), "Tavares de Lyra");
+    assert_eq!(
+        row.extras(),
+        &[("Observação".to_owned(), "rasgada".to_owned())]
+    );
+
+    let plain = row.plain_values();
+    assert_eq!(plain[0].as_deref(), Some("1905; 1906-1907"));
+    assert_eq!(plain[6].as_deref(), Some("Abel; Tavares de Lyra"));
+
+    let json = serde_json::to_value(&row).unwrap();
+    assert_eq!(
+        json["doner"],
+        serde_json::json!(["Abel", "Tavares de Lyra"])
+    );
+    assert_eq!(
+        json["extra"]["Observação"],
+        serde_json::json!(["rasgada", "sem capa"])
+    );
+}
@@ -6,10 +6,14 @@ use std::{
 
 use lazy_static::lazy_static;
 use regex::Regex;
+use serde::Serialize;
 
-use super::Range;
+use super::{
+    plain::{Plain, PlainText},
+    Range,
+};
 
-#[derive(PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
 pub struct NumberVolume {
     number: Option<Range>,
     volume: Option<Range>,
@@ -32,12 +36,8 @@ impl FromStr for NumberVolume {
         match (num_captures, vol_captures) {
             (None, None) => Err(()),
             (num, vol) => Ok(NumberVolume {
-                number: num
-                    .map(|cap| cap.get(1).unwrap().as_str().parse().ok())
-                    .flatten(),
-                volume: vol
-                    .map(|cap| cap.get(1).unwrap().as_str().parse().ok())
-                    .flatten(),
+                number: num.and_then(|cap| cap.get(1).unwrap().as_str().parse().ok()),
+                volume: vol.and_then(|cap| cap.get(1).unwrap().as_str().parse().ok()),
             }),
         }
     }
@@ -55,6 +55,18 @@ impl Display for NumberVolume {
     }
 }
 
+impl Plain for NumberVolume {
+    fn fmt_plain(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (&self.number, &self.volume) {
+            // Safity: NumberVolume struct ensure at least one of number or volume is Some
+            (None, None) => unsafe { unreachable_unchecked() },
+            (Some(n), Some(v)) => write!(f, "N.{} V.{}", PlainText(n), PlainText(v)),
+            (Some(n), None) => write!(f, "N.{}", PlainText(n)),
+            (None, Some(v)) => write!(f, "V.{}", PlainText(v)),
+        }
+    }
+}
+
 impl Debug for NumberVolume {
     #[inline]
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -3,9 +3,12 @@ use std::{
     str::FromStr,
 };
 
-use super::Range;
+use serde::Serialize;
 
-#[derive(PartialEq, Eq, PartialOrd, Ord)]
+use super::{plain::Plain, Range};
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(transparent)]
 pub struct Page(Range);
 
 impl From<Range> for Page {
@@ -28,6 +31,13 @@ impl Display for Page {
     }
 }
 
+impl Plain for Page {
+    #[inline]
+    fn fmt_plain(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt_plain(f)
+    }
+}
+
 impl Debug for Page {
     #[inline]
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -202,7 +212,7 @@ fn it_works() {
         ("281/282", Ok(Page((281..=282).into()))),
         ("325/326", Ok(Page((325..=326).into()))),
         ("p.406-407", Ok(Page((406..=407).into()))),
-        ("p.445", Ok(Page(455.into()))),
+        ("p.445", Ok(Page(445.into()))),
         ("310/311", Ok(Page((310..=311).into()))),
         ("296/297", Ok(Page((296..=297).into()))),
         ("204 - 205", Ok(Page((204..=205).into()))),
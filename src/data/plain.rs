@@ -0,0 +1,39 @@
+use std::fmt::{self, Display};
+
+use super::Field;
+
+/// Values that can be written back as plain text, the way they would be
+/// typed in the tables, so that parsing the text gives the same value again.
+///
+/// `Display` is meant for the printed catalog (abbreviations, `~`, index
+/// entries) and does not round trip.
+pub trait Plain {
+    fn fmt_plain(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result;
+}
+
+/// Displays a value through its [`Plain`] representation.
+pub struct PlainText<'a, T: ?Sized>(pub &'a T);
+
+impl<'a, T: Plain + ?Sized> Display for PlainText<'a, T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt_plain(f)
+    }
+}
+
+impl Plain for String {
+    #[inline]
+    fn fmt_plain(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self)
+    }
+}
+
+impl<T: Plain> Plain for Field<T> {
+    fn fmt_plain(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Field::Absent => f.write_str("-"),
+            Field::Present(t) => t.fmt_plain(f),
+            Field::NotStandard(s) => f.write_str(s),
+        }
+    }
+}
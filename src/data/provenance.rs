@@ -0,0 +1,36 @@
+use std::fmt::{self, Display};
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+/// Where the value of a field was read: the input file, the table (counted
+/// from 1, the sheet in spreadsheets) and the row inside it, the caption of
+/// the table and the text of the cell as it was typed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Provenance {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<PathBuf>,
+    pub table: usize,
+    pub row: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub caption: Option<String>,
+    /// The cell text before trimming and normalization.
+    pub raw: String,
+}
+
+impl Display for Provenance {
+    /// `a.docx, table 3 (Tabela 57), row 2`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(file) = self.file.as_ref() {
+            write!(f, "{}, ", file.display())?;
+        }
+
+        write!(f, "table {}", self.table)?;
+
+        if let Some(caption) = self.caption.as_ref() {
+            write!(f, " ({})", caption)?;
+        }
+
+        write!(f, ", row {}", self.row)
+    }
+}
@@ -1,5 +1,7 @@
 use lazy_static::lazy_static;
 use regex::Regex;
+use super::plain::Plain;
+use serde::{ser::SerializeStruct, Serialize, Serializer};
 use std::{
     convert::TryFrom,
     fmt::{self, Debug, Display},
@@ -7,7 +9,7 @@ use std::{
     str::FromStr,
 };
 
-#[derive(PartialEq, Eq, Hash)]
+#[derive(Clone, PartialEq, Eq, Hash)]
 pub struct Range<Idx = u32>(RangeInclusive<Idx>);
 
 impl<Idx> From<RangeInclusive<Idx>> for Range<Idx> {
@@ -73,6 +75,25 @@ where
     }
 }
 
+impl<Idx: Display + PartialEq> Plain for Range<Idx> {
+    fn fmt_plain(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.start() == self.0.end() {
+            write!(f, "{}", self.0.start())
+        } else {
+            write!(f, "{}-{}", self.0.start(), self.0.end())
+        }
+    }
+}
+
+impl<Idx: Serialize> Serialize for Range<Idx> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut range = serializer.serialize_struct("Range", 2)?;
+        range.serialize_field("start", self.0.start())?;
+        range.serialize_field("end", self.0.end())?;
+        range.end()
+    }
+}
+
 fn format_range<Idx: Display + PartialEq>(
     range: &RangeInclusive<Idx>,
     f: &mut fmt::Formatter,
@@ -0,0 +1,156 @@
+use std::fmt;
+
+use serde::Serialize;
+
+use super::Plain;
+
+/// Formatting of a run of text.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct Style {
+    #[serde(skip_serializing_if = "is_false")]
+    pub italic: bool,
+    #[serde(skip_serializing_if = "is_false")]
+    pub bold: bool,
+    #[serde(skip_serializing_if = "is_false")]
+    pub superscript: bool,
+    #[serde(skip_serializing_if = "is_false")]
+    pub small_caps: bool,
+    /// Highlighted text (`w:highlight`, a background color).
+    #[serde(skip_serializing_if = "is_false")]
+    pub highlight: bool,
+    /// Text on a shaded background (`w:shd`).
+    #[serde(skip_serializing_if = "is_false")]
+    pub shading: bool,
+    /// Struck through text.
+    #[serde(skip_serializing_if = "is_false")]
+    pub strike: bool,
+}
+
+pub(super) fn is_false(b: &bool) -> bool {
+    !b
+}
+
+impl Style {
+    pub fn is_plain(&self) -> bool {
+        *self == Style::default()
+    }
+
+    /// The formatting of text inside a run of this style, with the
+    /// formatting of its own added.
+    pub fn with(self, other: Style) -> Style {
+        Style {
+            italic: self.italic || other.italic,
+            bold: self.bold || other.bold,
+            superscript: self.superscript || other.superscript,
+            small_caps: self.small_caps || other.small_caps,
+            highlight: self.highlight || other.highlight,
+            shading: self.shading || other.shading,
+            strike: self.strike || other.strike,
+        }
+    }
+}
+
+/// A run of text of a single style.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Span {
+    pub text: String,
+    #[serde(flatten)]
+    pub style: Style,
+}
+
+/// Text as typed in a cell, with the formatting of its runs (a periodical
+/// title in italics, the "º" of an ordinal as a superscript).
+///
+/// The plain form is the text without formatting. Serialized as the list of
+/// its spans, each with the formatting it has.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(transparent)]
+pub struct RichText {
+    spans: Vec<Span>,
+}
+
+impl RichText {
+    /// Text without formatting.
+    pub fn plain(text: &str) -> Self {
+        let mut rich = Self::default();
+        rich.push(Style::default(), text);
+        rich
+    }
+
+    pub fn spans(&self) -> &[Span] {
+        &self.spans
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.spans.is_empty()
+    }
+
+    /// Whether no run has any formatting.
+    pub fn is_plain(&self) -> bool {
+        self.spans.iter().all(|span| span.style.is_plain())
+    }
+
+    /// Appends text, joined to the last span when it has the same style.
+    pub fn push(&mut self, style: Style, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+
+        match self.spans.last_mut() {
+            Some(last) if last.style == style => last.text.push_str(text),
+            _ => self.spans.push(Span {
+                text: text.to_owned(),
+                style,
+            }),
+        }
+    }
+
+    pub fn extend(&mut self, other: RichText) {
+        for span in other.spans {
+            self.push(span.style, &span.text);
+        }
+    }
+
+    /// Removes the whitespace at both ends, as the plain value of a cell is
+    /// trimmed.
+    pub fn trim(mut self) -> Self {
+        while let Some(first) = self.spans.first_mut() {
+            first.text = first.text.trim_start().to_owned();
+
+            if !first.text.is_empty() {
+                break;
+            }
+
+            self.spans.remove(0);
+        }
+
+        while let Some(last) = self.spans.last_mut() {
+            last.text = last.text.trim_end().to_owned();
+
+            if !last.text.is_empty() {
+                break;
+            }
+
+            self.spans.pop();
+        }
+
+        self
+    }
+
+    /// Replaces a pattern in the text of every span.
+    pub fn replace(&mut self, from: &str, to: &str) {
+        for span in self.spans.iter_mut() {
+            span.text = span.text.replace(from, to);
+        }
+    }
+}
+
+impl Plain for RichText {
+    fn fmt_plain(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for span in &self.spans {
+            f.write_str(&span.text)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,357 @@
+use std::fmt::{self, Display};
+use std::fs;
+use std::io::Error as IoError;
+use std::ops::Range;
+use std::path::Path;
+
+use regex::Regex;
+use serde::Deserialize;
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+
+/// Kind of value a field holds, which decides how its text is parsed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ValueType {
+    /// A year or a range of years: `1903`, `1903-1904`.
+    Range,
+    /// `p.138`, `pp. 10-12`.
+    Page,
+    /// Number and date of a session: `5ª sessão de 17 de agosto de 1902`.
+    Ata,
+    /// `N.2 V.2`.
+    NumberVolume,
+    /// `Revista/Jornal`.
+    DocumentTypes,
+    /// Free text.
+    Text,
+}
+
+/// One field of the catalog as declared in a schema file.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FieldSpec {
+    /// Identifier of the field, used in JSON exports and sort keys.
+    pub name: String,
+    /// Heading of the field in CSV/TSV exports. It must itself match one of
+    /// `patterns` so exported files can be read back.
+    pub key: String,
+    /// Label printed in the catalog.
+    pub label: String,
+    #[serde(rename = "type")]
+    pub kind: ValueType,
+    /// Regular expressions matched against the normalized table key (see
+    /// [`normalize_key`]), so they are written in lowercase, without accents
+    /// nor punctuation.
+    pub patterns: Vec<String>,
+    /// Whether the field gives the year of the catalog section of the row,
+    /// see [`Row::section_year`](super::Row::section_year).
+    #[serde(default)]
+    pub section: bool,
+    /// Whether the value gets an entry in the LaTeX index.
+    #[serde(default)]
+    pub index: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SchemaFile {
+    field: Vec<FieldSpec>,
+}
+
+/// Why a schema could not be loaded.
+#[derive(Debug)]
+pub enum SchemaError {
+    Io(IoError),
+    /// The file is not valid TOML or JSON, or does not describe fields.
+    Syntax(String),
+    /// A key pattern is not a valid regular expression.
+    Pattern(String, regex::Error),
+    /// Two fields have the same name.
+    DuplicateField(String),
+    /// The canonical key of a field is claimed by another field.
+    KeyMismatch(String),
+    /// The file extension is neither `.toml` nor `.json`.
+    UnknownFormat,
+}
+
+impl Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SchemaError::Io(e) => write!(f, "{}", e),
+            SchemaError::Syntax(e) => write!(f, "{}", e),
+            SchemaError::Pattern(field, e) => write!(f, "field {}: {}", field, e),
+            SchemaError::DuplicateField(field) => write!(f, "field {} is declared twice", field),
+            SchemaError::KeyMismatch(field) => {
+                write!(f, "the key of field {} does not name that field", field)
+            }
+            SchemaError::UnknownFormat => f.write_str("schemas are .toml or .json files"),
+        }
+    }
+}
+
+impl std::error::Error for SchemaError {}
+
+impl From<IoError> for SchemaError {
+    fn from(e: IoError) -> Self {
+        SchemaError::Io(e)
+    }
+}
+
+/// The fields of a catalog: what each table key means, how its value is
+/// read and how it is printed.
+///
+/// The order of the fields is the output order of the catalog.
+#[derive(Debug)]
+pub struct Schema {
+    fields: Vec<FieldSpec>,
+    patterns: Vec<Vec<Regex>>,
+}
+
+/// Brings a table key to the form field patterns are matched against:
+/// lowercase, without diacritics, punctuation turned into spaces and spaces
+/// collapsed. `"  Número/volume da Revista:"` becomes `"numero volume da
+/// revista"`.
+pub fn normalize_key(key: &str) -> String {
+    let key: String = key
+        .nfkd()
+        .filter(|c| !is_combining_mark(*c))
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect::<String>()
+        .to_lowercase();
+
+    key.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+const DEFAULT_SCHEMA: &str = include_str!("schema.toml");
+
+impl Default for Schema {
+    /// The catalog of periodicals the tool was written for.
+    fn default() -> Self {
+        Self::from_toml(DEFAULT_SCHEMA).expect("the default schema is valid")
+    }
+}
+
+impl Schema {
+    pub fn new(fields: Vec<FieldSpec>) -> Result<Self, SchemaError> {
+        let mut patterns = Vec::with_capacity(fields.len());
+
+        for (i, field) in fields.iter().enumerate() {
+            if fields[..i].iter().any(|f| f.name == field.name) {
+                return Err(SchemaError::DuplicateField(field.name.clone()));
+            }
+
+            let regexes = field
+                .patterns
+                .iter()
+                .map(|p| Regex::new(p).map_err(|e| SchemaError::Pattern(field.name.clone(), e)))
+                .collect::<Result<_, _>>()?;
+
+            patterns.push(regexes);
+        }
+
+        let schema = Self { fields, patterns };
+
+        for (i, field) in schema.fields.iter().enumerate() {
+            if schema.classify(&field.key) != Some(i) {
+                return Err(SchemaError::KeyMismatch(field.name.clone()));
+            }
+        }
+
+        Ok(schema)
+    }
+
+    pub fn from_toml(s: &str) -> Result<Self, SchemaError> {
+        let file: SchemaFile = toml::from_str(s).map_err(|e| SchemaError::Syntax(e.to_string()))?;
+        Self::new(file.field)
+    }
+
+    pub fn from_json(s: &str) -> Result<Self, SchemaError> {
+        let file: SchemaFile =
+            serde_json::from_str(s).map_err(|e| SchemaError::Syntax(e.to_string()))?;
+        Self::new(file.field)
+    }
+
+    /// Reads a `.toml` or `.json` schema file.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, SchemaError> {
+        let path = path.as_ref();
+
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("toml") => Self::from_toml(&fs::read_to_string(path)?),
+            Some("json") => Self::from_json(&fs::read_to_string(path)?),
+            _ => Err(SchemaError::UnknownFormat),
+        }
+    }
+
+    #[inline]
+    pub fn fields(&self) -> &[FieldSpec] {
+        &self.fields
+    }
+
+    /// Position of the field called `name`.
+    pub fn position(&self, name: &str) -> Option<usize> {
+        self.fields.iter().position(|f| f.name == name)
+    }
+
+    /// Positions of the fields a table key may belong to, best match first.
+    ///
+    /// The key is normalized (see [`normalize_key`]) and matched against the
+    /// patterns of every field. A field scores the length of the longest text
+    /// its patterns match, ties go to the field declared first. A field whose
+    /// match lies inside the best one is left out, the longer pattern already
+    /// tells what that part of the key means: `"^numero.*\bata\b"` takes
+    /// `"numero e data da ata"` from `"^numero"`.
+    pub fn matches(&self, key: &str) -> Vec<usize> {
+        let key = normalize_key(key);
+
+        let mut scores: Vec<(usize, usize, Range<usize>)> = self
+            .patterns
+            .iter()
+            .enumerate()
+            .filter_map(|(i, patterns)| {
+                patterns
+                    .iter()
+                    .filter_map(|p| p.find(&key))
+                    .max_by_key(|m| m.as_str().chars().count())
+                    .map(|m| (i, m.as_str().chars().count(), m.range()))
+            })
+            .collect();
+
+        scores.sort_by(|(li, ls, _), (ri, rs, _)| rs.cmp(ls).then(li.cmp(ri)));
+
+        let best = match scores.first() {
+            Some((_, _, best)) => best.clone(),
+            None => return Vec::new(),
+        };
+
+        scores
+            .into_iter()
+            .enumerate()
+            .filter(|(n, (_, _, m))| *n == 0 || m.start < best.start || m.end > best.end)
+            .map(|(_, (i, _, _))| i)
+            .collect()
+    }
+
+    /// Position of the field a table key belongs to, the best of
+    /// [`matches`](Self::matches).
+    pub fn classify(&self, key: &str) -> Option<usize> {
+        self.matches(key).first().copied()
+    }
+}
+
+#[test]
+fn default_schema() {
+    let schema = Schema::default();
+
+    assert_eq!(schema.classify("Ano da Revista"), schema.position("year"));
+    assert_eq!(
+        schema.classify("Número/volume da revista"),
+        schema.position("number_volume")
+    );
+    assert_eq!(
+        schema.classify("Número e data da Ata de sessão"),
+        schema.position("ata")
+    );
+    assert_eq!(
+        schema.matches("Número e data da Ata de sessão"),
+        schema.position("ata").into_iter().collect::<Vec<_>>()
+    );
+    assert_eq!(schema.matches("Ano da ata").len(), 2);
+    assert_eq!(schema.classify("Número"), schema.position("number_volume"));
+    assert_eq!(schema.classify("Numero"), schema.position("number_volume"));
+    assert_eq!(schema.classify("Pagina"), schema.position("page"));
+    assert_eq!(
+        normalize_key(" Número/volume da Revista:"),
+        "numero volume da revista"
+    );
+    assert_eq!(schema.classify("Quem doou?"), schema.position("doner"));
+    assert_eq!(
+        schema.classify(": Título completo"),
+        schema.position("title")
+    );
+    assert_eq!(schema.classify("Data de publicação"), None);
+    assert_eq!(schema.classify("Coluna nova"), None);
+}
+
+#[test]
+fn schema_files() {
+    let toml = r#"
+        [[field]]
+        name = "year"
+        key = "Ano"
+        label = "Ano da revista"
+        type = "range"
+        patterns = ["^ano"]
+        section = true
+
+        [[field]]
+        name = "title"
+        key = "Título"
+        label = "Título"
+        type = "text"
+        patterns = ["titulo"]
+        index = true
+    "#;
+    let json = r#"{"field": [
+        {"name": "year", "key": "Ano", "label": "Ano da revista", "type": "range",
+         "patterns": ["^ano"], "section": true},
+        {"name": "title", "key": "Título", "label": "Título", "type": "text",
+         "patterns": ["titulo"], "index": true}
+    ]}"#;
+
+    let dir = std::env::temp_dir().join(format!("parse-tables-schema-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("schema.toml"), toml).unwrap();
+    fs::write(dir.join("schema.json"), json).unwrap();
+    fs::write(dir.join("schema.yaml"), toml).unwrap();
+
+    let loaded = [
+        Schema::load(dir.join("schema.toml")),
+        Schema::load(dir.join("schema.json")),
+    ];
+    let unknown = Schema::load(dir.join("schema.yaml"));
+    fs::remove_dir_all(&dir).unwrap();
+
+    for schema in loaded {
+        let schema = schema.unwrap();
+        let fields = schema.fields();
+
+        assert_eq!(fields.len(), 2);
+        assert_eq!(fields[0].kind, ValueType::Range);
+        assert!(fields[0].section && !fields[0].index);
+        assert_eq!(fields[1].label, "Título");
+        assert!(fields[1].index && !fields[1].section);
+        assert_eq!(schema.classify("Ano de publicação"), Some(0));
+        assert_eq!(schema.classify("Título completo"), Some(1));
+    }
+
+    assert!(matches!(unknown, Err(SchemaError::UnknownFormat)));
+    assert!(matches!(
+        Schema::from_json(r#"{"field": [{"name": "year"}]}"#),
+        Err(SchemaError::Syntax(_))
+    ));
+
+    let field = |name: &str, key: &str, pattern: &str| {
+        format!(
+            "[[field]]\nname = \"{}\"\nkey = \"{}\"\nlabel = \"{}\"\ntype = \"text\"\n\
+             patterns = [\"{}\"]\n",
+            name, key, key, pattern
+        )
+    };
+
+    match Schema::from_toml(&(field("title", "Título", "titulo") + &field("title", "Nome", "nome")))
+    {
+        Err(SchemaError::DuplicateField(name)) => assert_eq!(name, "title"),
+        other => panic!("{:?}", other.map(|_| "a schema")),
+    }
+    match Schema::from_toml(
+        &(field("title", "Título", "titulo") + &field("doner", "Título do doador", "doador")),
+    ) {
+        Err(SchemaError::KeyMismatch(name)) => assert_eq!(name, "doner"),
+        other => panic!("{:?}", other.map(|_| "a schema")),
+    }
+    match Schema::from_toml(&field("title", "Título", "titulo(")) {
+        Err(SchemaError::Pattern(name, _)) => assert_eq!(name, "title"),
+        other => panic!("{:?}", other.map(|_| "a schema")),
+    }
+}
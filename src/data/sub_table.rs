@@ -0,0 +1,45 @@
+use serde::Serialize;
+
+/// A table typed inside a cell, such as the documents of a bundle listed in
+/// its title. Each line holds the text of its cells.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(transparent)]
+pub struct SubTable {
+    lines: Vec<Vec<String>>,
+}
+
+impl SubTable {
+    /// Trims the cells and drops the lines whose cells are all empty.
+    pub fn new(lines: Vec<Vec<String>>) -> Self {
+        let lines = lines
+            .into_iter()
+            .map(|cells| {
+                cells
+                    .iter()
+                    .map(|cell| cell.split_whitespace().collect::<Vec<_>>().join(" "))
+                    .collect::<Vec<_>>()
+            })
+            .filter(|cells| cells.iter().any(|cell| !cell.is_empty()))
+            .collect();
+
+        Self { lines }
+    }
+
+    pub fn lines(&self) -> &[Vec<String>] {
+        &self.lines
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    /// Whether the table has a single column, making it a list of lines.
+    pub fn is_list(&self) -> bool {
+        self.lines.iter().all(|cells| cells.len() <= 1)
+    }
+
+    /// Adds the lines of another table after the ones of this table.
+    pub fn extend(&mut self, other: SubTable) {
+        self.lines.extend(other.lines);
+    }
+}
@@ -0,0 +1,109 @@
+use std::convert::TryFrom;
+use std::fmt::{self, Display};
+
+use serde::{Serialize, Serializer};
+
+use super::schema::ValueType;
+use super::{Ata, DocumentTypes, Field, NumberVolume, Page, Plain, Range};
+
+/// The value of a field of a [`Row`](super::Row), parsed according to the
+/// [`ValueType`] of the field.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Value {
+    Range(Field<Range>),
+    Page(Field<Page>),
+    Ata(Field<Ata>),
+    NumberVolume(Field<NumberVolume>),
+    DocumentTypes(DocumentTypes),
+    Text(Field<String>),
+}
+
+impl Value {
+    /// Parses the text of a table cell.
+    pub fn parse(kind: ValueType, s: &str) -> Self {
+        fn parse<T: std::str::FromStr<Err = never::Never>>(s: &str) -> T {
+            match s.parse() {
+                Ok(t) => t,
+                Err(never) => match never {},
+            }
+        }
+
+        match kind {
+            ValueType::Range => Value::Range(parse(s)),
+            ValueType::Page => Value::Page(parse(s)),
+            ValueType::Ata => Value::Ata(parse(s)),
+            ValueType::NumberVolume => Value::NumberVolume(parse(s)),
+            ValueType::DocumentTypes => Value::DocumentTypes(parse(&s.to_lowercase())),
+            ValueType::Text => Value::Text(parse(&s.replace("°", "º"))),
+        }
+    }
+
+    /// Year the value refers to, if it is a year or a dated session.
+    pub fn year(&self) -> Option<Range> {
+        match self {
+            Value::Range(Field::Present(year)) => Some(year.clone()),
+            Value::Ata(Field::Present(ata)) => u32::try_from(ata.year()).ok().map(Range::from),
+            _ => None,
+        }
+    }
+
+    /// Whether the text could not be read in the form of the field, the value
+    /// keeping it as typed.
+    pub fn is_not_standard(&self) -> bool {
+        matches!(
+            self,
+            Value::Range(Field::NotStandard(_))
+                | Value::Page(Field::NotStandard(_))
+                | Value::Ata(Field::NotStandard(_))
+                | Value::NumberVolume(Field::NotStandard(_))
+                | Value::Text(Field::NotStandard(_))
+        )
+    }
+
+    /// The text of a `Text` value as typed in the table.
+    pub fn as_text(&self) -> Option<&Field<String>> {
+        match self {
+            Value::Text(text) => Some(text),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Range(v) => v.fmt(f),
+            Value::Page(v) => v.fmt(f),
+            Value::Ata(v) => v.fmt(f),
+            Value::NumberVolume(v) => v.fmt(f),
+            Value::DocumentTypes(v) => v.fmt(f),
+            Value::Text(v) => v.fmt(f),
+        }
+    }
+}
+
+impl Plain for Value {
+    fn fmt_plain(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Range(v) => v.fmt_plain(f),
+            Value::Page(v) => v.fmt_plain(f),
+            Value::Ata(v) => v.fmt_plain(f),
+            Value::NumberVolume(v) => v.fmt_plain(f),
+            Value::DocumentTypes(v) => v.fmt_plain(f),
+            Value::Text(v) => v.fmt_plain(f),
+        }
+    }
+}
+
+impl Serialize for Value {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Value::Range(v) => v.serialize(serializer),
+            Value::Page(v) => v.serialize(serializer),
+            Value::Ata(v) => v.serialize(serializer),
+            Value::NumberVolume(v) => v.serialize(serializer),
+            Value::DocumentTypes(v) => v.serialize(serializer),
+            Value::Text(v) => v.serialize(serializer),
+        }
+    }
+}
@@ -0,0 +1,199 @@
+use std::io::{Read, Write};
+use std::rc::Rc;
+
+use csv::{ReaderBuilder, WriterBuilder};
+
+use crate::data::{Row, Schema};
+use crate::diagnostic::Location;
+use crate::records::read_records;
+use crate::render::Renderer;
+use crate::{CallError, ReadOptions, RowOrParagraph};
+
+/// Writes rows as delimiter separated values (`b','` for CSV, `b'\t'` for
+/// TSV), one column per field of `schema` headed by the key of the field,
+/// followed by one column per extra field found in the rows.
+///
+/// A field the row does not have is an empty cell, an absent value ("não
+/// mencionado", "?", ...) is written as `-` and values that could not be
+/// understood are written as they were typed, so reading the file back with
+/// [`read_delimited`] gives the same rows.
+pub fn write_delimited<'a, W, I>(
+    mut w: W,
+    schema: &Rc<Schema>,
+    rows: I,
+    delimiter: u8,
+) -> Result<(), CallError>
+where
+    W: Write,
+    I: IntoIterator<Item = &'a Row>,
+{
+    let mut renderer = DelimitedRenderer::new(schema.clone(), delimiter);
+
+    for row in rows {
+        renderer.row(&mut w, row)?;
+    }
+    renderer.end_document(&mut w)
+}
+
+/// Renders rows as delimiter separated values, see [`write_delimited`].
+/// Sections and paragraphs are left out.
+///
+/// The columns of extra fields are only known once every row is seen, so
+/// the file is written by `end_document`.
+pub struct DelimitedRenderer {
+    schema: Rc<Schema>,
+    delimiter: u8,
+    extra_keys: Vec<String>,
+    records: Vec<Vec<String>>,
+}
+
+impl DelimitedRenderer {
+    pub fn new(schema: Rc<Schema>, delimiter: u8) -> Self {
+        Self {
+            schema,
+            delimiter,
+            extra_keys: Vec::new(),
+            records: Vec::new(),
+        }
+    }
+
+    fn write_records(&mut self, w: &mut dyn Write) -> Result<(), csv::Error> {
+        let mut writer = WriterBuilder::new()
+            .delimiter(self.delimiter)
+            .from_writer(w);
+
+        let keys = self.schema.fields().iter().map(|f| f.key.as_str());
+        writer.write_record(keys.chain(self.extra_keys.iter().map(String::as_str)))?;
+
+        let columns = self.schema.fields().len() + self.extra_keys.len();
+        for mut record in self.records.drain(..) {
+            record.resize(columns, String::new());
+            writer.write_record(&record)?;
+        }
+
+        writer.flush()?;
+
+        Ok(())
+    }
+}
+
+impl Renderer for DelimitedRenderer {
+    fn row(&mut self, _w: &mut dyn Write, row: &Row) -> Result<(), CallError> {
+        let mut record: Vec<String> = row
+            .plain_values()
+            .into_iter()
+            .map(Option::unwrap_or_default)
+            .collect();
+
+        let fields = record.len();
+
+        for (key, value) in row.extras() {
+            let column = match self.extra_keys.iter().position(|k| k == key) {
+                Some(column) => column,
+                None => {
+                    self.extra_keys.push(key.clone());
+                    self.extra_keys.len() - 1
+                }
+            };
+
+            if record.len() <= fields + column {
+                record.resize(fields + column + 1, String::new());
+            }
+            record[fields + column] = row.joined(key, value.clone());
+        }
+
+        self.records.push(record);
+
+        Ok(())
+    }
+
+    fn end_document(&mut self, w: &mut dyn Write) -> Result<(), CallError> {
+        self.write_records(w)
+            .map_err(|e| CallError::Output(e.into()))
+    }
+}
+
+/// Reads rows written by [`write_delimited`], or any file whose first line
+/// holds table keys as headings.
+pub fn read_delimited<R: Read>(
+    r: R,
+    options: &ReadOptions,
+    delimiter: u8,
+) -> Result<Vec<RowOrParagraph>, CallError> {
+    let mut reader = ReaderBuilder::new()
+        .delimiter(delimiter)
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(r);
+
+    let mut lines = Vec::new();
+
+    for record in reader.records() {
+        let record = record?;
+        let line = record.position().map_or(0, |p| p.line() as usize);
+
+        lines.push((line, record.iter().map(str::to_owned).collect()));
+    }
+
+    Ok(read_records(options, lines.into_iter(), |line, cell| {
+        Location {
+            table: 1,
+            caption: None,
+            row: line,
+            cell: cell + 1,
+            position: None,
+        }
+    }))
+}
+
+#[test]
+fn round_trip() {
+    let schema = Rc::new(Schema::default());
+    let row = |fields: &[(&str, &str)]| {
+        let mut row = Row::new(schema.clone());
+
+        for (key, value) in fields {
+            row.set_field((key.to_string(), value.to_string())).ok();
+        }
+
+        row
+    };
+
+    let rows = vec![
+        row(&[
+            ("Ano da Revista", "1913"),
+            (
+                "Número e data da Ata de sessão",
+                "8ª sessão de 9 de maio de 1913",
+            ),
+            ("Página", "p.7"),
+            (
+                "Título completo do manuscrito",
+                "Carta, \"O Panorama\"\tanexa",
+            ),
+            ("Quem doou?", "não mencionado"),
+            ("Observação", "rasgado"),
+        ]),
+        row(&[
+            ("Ano da Revista", "por volta de 1900"),
+            ("Tipo de documento", "Revista"),
+            ("Estante", "B; 3"),
+        ]),
+    ];
+
+    for &delimiter in [b',', b'\t'].iter() {
+        let mut data = Vec::new();
+        write_delimited(&mut data, &schema, &rows, delimiter).unwrap();
+
+        let read: Vec<_> = read_delimited(&data[..], &ReadOptions::default(), delimiter)
+            .unwrap()
+            .into_iter()
+            .filter_map(|item| match item {
+                RowOrParagraph::Row(row) => Some(row),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(read, rows);
+    }
+}
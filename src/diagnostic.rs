@@ -0,0 +1,84 @@
+use std::fmt::Display;
+
+use xml::common::TextPosition;
+
+use crate::data::Schema;
+
+/// Where something was found in the document: the table (counted from 1 in
+/// document order) with its caption, the row inside that table and the cell
+/// inside that row, plus the position in the XML for tools that can jump to
+/// it.
+///
+/// In spreadsheets the table is the sheet and there is no caption nor XML
+/// position.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Location {
+    pub table: usize,
+    pub caption: Option<String>,
+    pub row: usize,
+    pub cell: usize,
+    pub position: Option<TextPosition>,
+}
+
+impl Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.table == 0 {
+            write!(f, "before the first table")?;
+        } else {
+            write!(f, "table {}", self.table)?;
+
+            if let Some(caption) = self.caption.as_ref() {
+                write!(f, " ({})", caption)?;
+            }
+
+            if self.row > 0 {
+                write!(f, ", row {}", self.row)?;
+            }
+
+            if self.cell > 0 {
+                write!(f, ", cell {}", self.cell)?;
+            }
+        }
+
+        match self.position {
+            Some(position) => write!(f, " (xml {})", position),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Something odd that did not stop the parsing, but that an editor should
+/// look at in the original document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Warning {
+    pub location: Location,
+    pub message: String,
+}
+
+impl Display for Warning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.location, self.message)
+    }
+}
+
+/// Message for a key that matches several fields of `schema`, naming the
+/// field it was given to.
+pub(crate) fn ambiguous_key(schema: &Schema, key: &str) -> Option<String> {
+    let matches = schema.matches(key);
+
+    if matches.len() < 2 {
+        return None;
+    }
+
+    let names: Vec<&str> = matches
+        .iter()
+        .map(|&i| schema.fields()[i].name.as_str())
+        .collect();
+
+    Some(format!(
+        "key {:?} matches the fields {}, it was taken as {}",
+        key.trim(),
+        names.join(", "),
+        names[0]
+    ))
+}
@@ -0,0 +1,115 @@
+use std::fmt::Display;
+use std::io::Error as IoError;
+use std::path::PathBuf;
+
+use calamine::Error as SpreadsheetError;
+use csv::Error as CsvError;
+use serde_json::Error as JsonError;
+use xml::reader::Error as XmlError;
+use zip::result::ZipError;
+
+use crate::parse::ParseError;
+use crate::revision::Revision;
+
+#[derive(Debug)]
+pub enum CallError {
+    /// Reading a document failed.
+    Io(IoError),
+    /// Writing the catalog failed.
+    Output(IoError),
+    Xml(Box<XmlError>),
+    Parse(ParseError),
+    Zip(ZipError),
+    Spreadsheet(SpreadsheetError),
+    Csv(CsvError),
+    Json(JsonError),
+    UnknownFormat(PathBuf),
+    /// The document has revisions, which were asked to be refused.
+    Revisions(Vec<Revision>),
+    AlreadyFinished,
+    Paragraph(String),
+}
+
+impl From<IoError> for CallError {
+    fn from(e: IoError) -> Self {
+        CallError::Io(e)
+    }
+}
+
+impl From<XmlError> for CallError {
+    fn from(e: XmlError) -> Self {
+        CallError::Xml(Box::new(e))
+    }
+}
+
+impl From<ParseError> for CallError {
+    fn from(e: ParseError) -> Self {
+        CallError::Parse(e)
+    }
+}
+
+impl From<ZipError> for CallError {
+    fn from(e: ZipError) -> Self {
+        CallError::Zip(e)
+    }
+}
+
+impl From<SpreadsheetError> for CallError {
+    fn from(e: SpreadsheetError) -> Self {
+        CallError::Spreadsheet(e)
+    }
+}
+
+impl From<CsvError> for CallError {
+    fn from(e: CsvError) -> Self {
+        CallError::Csv(e)
+    }
+}
+
+impl From<JsonError> for CallError {
+    fn from(e: JsonError) -> Self {
+        CallError::Json(e)
+    }
+}
+
+impl Display for CallError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CallError::Io(e) => write!(f, "could not read the document: {}", e),
+            CallError::Output(e) => write!(f, "could not write the output: {}", e),
+            CallError::Xml(e) => write!(f, "malformed document XML at {}", e),
+            CallError::Parse(e) => write!(f, "{}", e),
+            CallError::Zip(e) => write!(f, "could not open the document archive: {}", e),
+            CallError::Spreadsheet(e) => write!(f, "could not read the spreadsheet: {}", e),
+            CallError::Csv(e) => write!(f, "malformed CSV: {}", e),
+            CallError::Json(e) => write!(f, "could not write JSON: {}", e),
+            CallError::UnknownFormat(_) => write!(f, "not a document nor a spreadsheet"),
+            CallError::Revisions(revisions) => {
+                write!(f, "the document has {} pending revisions:", revisions.len())?;
+
+                for revision in revisions {
+                    write!(f, "\n    {}", revision)?;
+                }
+
+                Ok(())
+            }
+            CallError::AlreadyFinished => write!(f, "the document was already read to the end"),
+            CallError::Paragraph(p) => write!(f, "paragraph outside tables: {:?}", p),
+        }
+    }
+}
+
+impl std::error::Error for CallError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CallError::Io(e) | CallError::Output(e) => Some(e),
+            CallError::Xml(e) => Some(e.as_ref()),
+            CallError::Parse(e) => Some(e),
+            CallError::Zip(e) => Some(e),
+            CallError::Spreadsheet(e) => Some(e),
+            CallError::Csv(e) => Some(e),
+            CallError::Json(e) => Some(e),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,325 @@
+use std::path::Path;
+
+use xml::attribute::OwnedAttribute;
+use xml::name::OwnedName;
+
+use crate::data::{NoteKind, Style};
+use crate::revision::RevisionKind;
+
+const TABLE_NS: &str = "urn:oasis:names:tc:opendocument:xmlns:table:1.0";
+const TEXT_NS: &str = "urn:oasis:names:tc:opendocument:xmlns:text:1.0";
+const OFFICE_NS: &str = "urn:oasis:names:tc:opendocument:xmlns:office:1.0";
+const STYLE_NS: &str = "urn:oasis:names:tc:opendocument:xmlns:style:1.0";
+const FO_NS: &str = "urn:oasis:names:tc:opendocument:xmlns:xsl-fo-compatible:1.0";
+
+/// The word processor formats the catalog can be read from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Office Open XML, as saved by Word (`.docx`).
+    Docx,
+    /// OpenDocument Text, as saved by LibreOffice (`.odt`).
+    Odt,
+}
+
+/// The elements of the document the parser cares about, whatever the format
+/// calls them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Element {
+    Table,
+    Row,
+    /// A table cell and the number of grid columns it spans, when given
+    /// as an attribute.
+    Cell(usize),
+    /// The number of grid columns spanned by the cell (`w:gridSpan`).
+    GridSpan(usize),
+    /// Marks the cell as the continuation of the cell above it
+    /// (`w:vMerge` without `restart`).
+    MergedCell,
+    /// A cell hidden by a cell spanning several columns or rows
+    /// (`table:covered-table-cell`).
+    CoveredCell,
+    Paragraph,
+    /// A run of text, `true` when its leading space must be kept.
+    Text(bool),
+    /// Spaces, tabs and line breaks typed as elements.
+    Space(usize),
+    /// Content that is not part of the text flow (annotations, notes, ...).
+    Ignored,
+    /// The instruction of a Word field (`SEQ Tabela \* ARABIC`), given as
+    /// the text of the element.
+    FieldCode,
+    /// A mark of a complex Word field (`w:fldChar`), which has its
+    /// instruction between the begin and separate marks and its result
+    /// between the separate and end marks.
+    FieldChar(FieldChar),
+    /// A field given with its instruction (`w:fldSimple`, `text:sequence`),
+    /// its content being the result of the field.
+    SimpleField(String),
+    /// A run of text of a single formatting (`w:r`), which its properties
+    /// come first in.
+    Run,
+    /// A formatting property of a run, `true` when it is turned on.
+    Emphasis(Emphasis, bool),
+    /// A style definition (`style:style`), with its name when it formats
+    /// text.
+    TextStyle(Option<String>),
+    /// The formatting a style gives to text (`style:text-properties`).
+    TextProperties(Style),
+    /// Text formatted with the named style (`text:span`).
+    StyledSpan(Option<String>),
+    /// A tracked change, with its author and date (`w:ins`, `w:del`). It
+    /// holds the runs changed, or marks the row or paragraph it is in as
+    /// changed.
+    Revision(RevisionKind, Option<String>, Option<String>),
+    /// The changes an OpenDocument tracks (`text:tracked-changes`), which
+    /// are not read.
+    TrackedChanges,
+    /// Where a comment is anchored (`w:commentRangeStart`, or the
+    /// `w:commentReference` of a comment without range), with the id of the
+    /// comment.
+    Comment(String),
+    /// Where a footnote or endnote is called, with the id of the note.
+    NoteReference(NoteKind, String),
+    Other,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum FieldChar {
+    Begin,
+    Separate,
+    End,
+}
+
+/// The formatting properties of a run kept in rich text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Emphasis {
+    Italic,
+    Bold,
+    Superscript,
+    SmallCaps,
+    Highlight,
+    Shading,
+    Strike,
+}
+
+impl Emphasis {
+    pub(crate) fn apply(self, style: &mut Style, on: bool) {
+        match self {
+            Emphasis::Italic => style.italic = on,
+            Emphasis::Bold => style.bold = on,
+            Emphasis::Superscript => style.superscript = on,
+            Emphasis::SmallCaps => style.small_caps = on,
+            Emphasis::Highlight => style.highlight = on,
+            Emphasis::Shading => style.shading = on,
+            Emphasis::Strike => style.strike = on,
+        }
+    }
+}
+
+impl Format {
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Option<Self> {
+        let extension = path.as_ref().extension()?.to_str()?.to_lowercase();
+
+        match extension.as_str() {
+            "docx" => Some(Format::Docx),
+            "odt" => Some(Format::Odt),
+            _ => None,
+        }
+    }
+
+    /// Name of the archive entry holding the document body.
+    pub fn entry(&self) -> &'static str {
+        match self {
+            Format::Docx => "word/document.xml",
+            Format::Odt => "content.xml",
+        }
+    }
+
+    /// Names of the archive entries holding the footnotes and endnotes of the
+    /// document, when they are kept apart from the body.
+    pub fn notes_entries(&self) -> &'static [(NoteKind, &'static str)] {
+        match self {
+            Format::Docx => &[
+                (NoteKind::Footnote, "word/footnotes.xml"),
+                (NoteKind::Endnote, "word/endnotes.xml"),
+            ],
+            Format::Odt => &[],
+        }
+    }
+
+    /// Name of the archive entry holding the comments of the document, when
+    /// they are kept apart from the body.
+    pub fn comments_entry(&self) -> Option<&'static str> {
+        match self {
+            Format::Docx => Some("word/comments.xml"),
+            Format::Odt => None,
+        }
+    }
+
+    pub(crate) fn element(&self, name: &OwnedName, attributes: &[OwnedAttribute]) -> Element {
+        match self {
+            Format::Docx => docx_element(name, attributes),
+            Format::Odt => odt_element(name, attributes),
+        }
+    }
+}
+
+pub(crate) fn attribute<'a>(attributes: &'a [OwnedAttribute], local_name: &str) -> Option<&'a str> {
+    attributes
+        .iter()
+        .find(|att| att.name.local_name == local_name)
+        .map(|att| att.value.as_str())
+}
+
+/// Whether a toggle property of a run (`<w:i/>`, `<w:b w:val="0"/>`) is on.
+fn toggle(attributes: &[OwnedAttribute]) -> bool {
+    !matches!(
+        attribute(attributes, "val"),
+        Some("0") | Some("false") | Some("off")
+    )
+}
+
+/// Whether a `w:shd` colors the text behind it: a pattern, or a fill other
+/// than white.
+fn shaded(attributes: &[OwnedAttribute]) -> bool {
+    let fill = attribute(attributes, "fill")
+        .unwrap_or("auto")
+        .to_lowercase();
+
+    match attribute(attributes, "val") {
+        Some("nil") => false,
+        Some("clear") | None => fill != "auto" && fill != "ffffff",
+        Some(_) => true,
+    }
+}
+
+/// The formatting given by the properties of an OpenDocument text style.
+fn text_properties(attributes: &[OwnedAttribute]) -> Style {
+    let fo = |name: &str| {
+        attributes
+            .iter()
+            .find(|att| att.name.namespace.as_deref() == Some(FO_NS) && att.name.local_name == name)
+            .map(|att| att.value.as_str())
+    };
+
+    let weight = fo("font-weight");
+    let position = attribute(attributes, "text-position").unwrap_or("");
+
+    Style {
+        italic: matches!(fo("font-style"), Some("italic") | Some("oblique")),
+        bold: weight == Some("bold") || weight.and_then(|w| w.parse::<u32>().ok()) >= Some(600),
+        superscript: position.starts_with("super")
+            || position
+                .split('%')
+                .next()
+                .and_then(|p| p.parse::<f64>().ok())
+                .is_some_and(|p| p > 0.0),
+        small_caps: fo("font-variant") == Some("small-caps"),
+        highlight: fo("background-color").is_some_and(|color| color != "transparent"),
+        shading: false,
+        strike: attribute(attributes, "text-line-through-style").is_some_and(|line| line != "none"),
+    }
+}
+
+fn revision(kind: RevisionKind, attributes: &[OwnedAttribute]) -> Element {
+    let author = attribute(attributes, "author").map(str::to_owned);
+    let date = attribute(attributes, "date").map(str::to_owned);
+
+    Element::Revision(kind, author, date)
+}
+
+fn note_reference(kind: NoteKind, attributes: &[OwnedAttribute]) -> Element {
+    Element::NoteReference(kind, attribute(attributes, "id").unwrap_or("").to_owned())
+}
+
+/// Number of columns given by a span attribute, 1 when there is none.
+fn span(value: Option<&str>) -> usize {
+    value.and_then(|v| v.parse().ok()).unwrap_or(1)
+}
+
+fn docx_element(name: &OwnedName, attributes: &[OwnedAttribute]) -> Element {
+    match name.local_name.as_str() {
+        "tbl" => Element::Table,
+        "tr" => Element::Row,
+        "tc" => Element::Cell(1),
+        "gridSpan" => Element::GridSpan(span(attribute(attributes, "val"))),
+        "vMerge" if attribute(attributes, "val") != Some("restart") => Element::MergedCell,
+        "p" => Element::Paragraph,
+        "t" | "delText" => Element::Text(attribute(attributes, "space") == Some("preserve")),
+        "br" | "cr" => Element::Space(1),
+        // `w:tab` is also a tab stop definition inside the paragraph
+        // properties, only the one without a position is typed text.
+        "tab" if attribute(attributes, "pos").is_none() => Element::Space(1),
+        "instrText" | "delInstrText" => Element::FieldCode,
+        "fldChar" => match attribute(attributes, "fldCharType") {
+            Some("begin") => Element::FieldChar(FieldChar::Begin),
+            Some("separate") => Element::FieldChar(FieldChar::Separate),
+            Some("end") => Element::FieldChar(FieldChar::End),
+            _ => Element::Other,
+        },
+        "fldSimple" => {
+            Element::SimpleField(attribute(attributes, "instr").unwrap_or("").to_owned())
+        }
+        "r" => Element::Run,
+        // The properties a tracked change replaced, which are not the ones
+        // of the text.
+        "rPrChange" | "pPrChange" | "tcPrChange" | "trPrChange" => Element::Ignored,
+        "i" => Element::Emphasis(Emphasis::Italic, toggle(attributes)),
+        "b" => Element::Emphasis(Emphasis::Bold, toggle(attributes)),
+        "smallCaps" => Element::Emphasis(Emphasis::SmallCaps, toggle(attributes)),
+        "vertAlign" => Element::Emphasis(
+            Emphasis::Superscript,
+            attribute(attributes, "val") == Some("superscript"),
+        ),
+        "commentRangeStart" | "commentReference" => {
+            Element::Comment(attribute(attributes, "id").unwrap_or("").to_owned())
+        }
+        "footnoteReference" => note_reference(NoteKind::Footnote, attributes),
+        "endnoteReference" => note_reference(NoteKind::Endnote, attributes),
+        "ins" | "moveTo" => revision(RevisionKind::Insertion, attributes),
+        "del" | "moveFrom" => revision(RevisionKind::Deletion, attributes),
+        "highlight" => Element::Emphasis(
+            Emphasis::Highlight,
+            attribute(attributes, "val") != Some("none"),
+        ),
+        "shd" => Element::Emphasis(Emphasis::Shading, shaded(attributes)),
+        "strike" | "dstrike" => Element::Emphasis(Emphasis::Strike, toggle(attributes)),
+        _ => Element::Other,
+    }
+}
+
+fn odt_element(name: &OwnedName, attributes: &[OwnedAttribute]) -> Element {
+    let namespace = name.namespace.as_deref();
+
+    match (namespace, name.local_name.as_str()) {
+        (Some(TABLE_NS), "table") => Element::Table,
+        (Some(TABLE_NS), "table-row") => Element::Row,
+        (Some(TABLE_NS), "table-cell") => {
+            Element::Cell(span(attribute(attributes, "number-columns-spanned")))
+        }
+        (Some(TABLE_NS), "covered-table-cell") => Element::CoveredCell,
+        (Some(TEXT_NS), "p") | (Some(TEXT_NS), "h") => Element::Paragraph,
+        (Some(TEXT_NS), "s") => {
+            let count = attribute(attributes, "c").and_then(|c| c.parse().ok());
+            Element::Space(count.unwrap_or(1))
+        }
+        (Some(TEXT_NS), "tab") | (Some(TEXT_NS), "line-break") => Element::Space(1),
+        (Some(TEXT_NS), "sequence") => {
+            let name = attribute(attributes, "name").unwrap_or("");
+            Element::SimpleField(format!("SEQ {}", name))
+        }
+        (Some(TEXT_NS), "note-body") => Element::Ignored,
+        (Some(TEXT_NS), "tracked-changes") => Element::TrackedChanges,
+        (Some(OFFICE_NS), "annotation") => Element::Ignored,
+        (Some(TEXT_NS), "span") => {
+            Element::StyledSpan(attribute(attributes, "style-name").map(str::to_owned))
+        }
+        (Some(STYLE_NS), "style") => Element::TextStyle(
+            attribute(attributes, "name")
+                .filter(|_| attribute(attributes, "family") == Some("text"))
+                .map(str::to_owned),
+        ),
+        (Some(STYLE_NS), "text-properties") => Element::TextProperties(text_properties(attributes)),
+        _ => Element::Other,
+    }
+}
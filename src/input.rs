@@ -0,0 +1,53 @@
+use std::fs::File;
+use std::path::Path;
+
+use crate::spreadsheet::read_spreadsheet;
+use crate::{read_delimited, read_document, CallError, Format, ReadOptions, RowOrParagraph};
+
+/// Rows, paragraphs and warnings read from one input file.
+pub type Items = Box<dyn Iterator<Item = Result<RowOrParagraph, CallError>>>;
+
+const SPREADSHEET_EXTENSIONS: [&str; 5] = ["xlsx", "xlsm", "xlsb", "xls", "ods"];
+
+fn is_spreadsheet(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| SPREADSHEET_EXTENSIONS.contains(&e.to_lowercase().as_str()))
+}
+
+fn delimiter(path: &Path) -> Option<u8> {
+    match path.extension()?.to_str()?.to_lowercase().as_str() {
+        "csv" => Some(b','),
+        "tsv" | "tab" => Some(b'\t'),
+        _ => None,
+    }
+}
+
+/// Whether `read_path` knows how to read the file, judging by its extension.
+pub fn is_supported<P: AsRef<Path>>(path: P) -> bool {
+    let path = path.as_ref();
+    Format::from_path(path).is_some() || is_spreadsheet(path) || delimiter(path).is_some()
+}
+
+/// Reads a document (docx, odt), a spreadsheet (xlsx, ods, ...) or a CSV/TSV
+/// file chosen by the file extension. Record boundaries only apply to
+/// documents, spreadsheets and delimited files have one record per line.
+pub fn read_path<P: AsRef<Path>>(path: P, options: &ReadOptions) -> Result<Items, CallError> {
+    let path = path.as_ref();
+    let options = &ReadOptions {
+        file: Some(path.to_owned()),
+        ..options.clone()
+    };
+
+    if let Some(format) = Format::from_path(path) {
+        Ok(Box::new(read_document(File::open(path)?, format, options)?))
+    } else if is_spreadsheet(path) {
+        let items = read_spreadsheet(File::open(path)?, options)?;
+        Ok(Box::new(items.into_iter().map(Ok)))
+    } else if let Some(delimiter) = delimiter(path) {
+        let items = read_delimited(File::open(path)?, options, delimiter)?;
+        Ok(Box::new(items.into_iter().map(Ok)))
+    } else {
+        Err(CallError::UnknownFormat(path.to_owned()))
+    }
+}
@@ -0,0 +1,70 @@
+use std::io::{self, Write};
+
+use crate::data::Row;
+use crate::render::Renderer;
+use crate::CallError;
+
+/// Writes rows as a JSON array, one object per row with the structured value
+/// of each field (see the `Serialize` impls in [`data`](crate::data)).
+pub fn write_json<'a, W, I>(mut w: W, rows: I) -> Result<(), CallError>
+where
+    W: Write,
+    I: IntoIterator<Item = &'a Row>,
+{
+    let mut renderer = JsonRenderer::new();
+
+    for row in rows {
+        renderer.row(&mut w, row)?;
+    }
+    renderer.end_document(&mut w)
+}
+
+/// Renders rows as a JSON array, see [`write_json`]. Rows are written as
+/// they come and the array is closed by `end_document`.
+#[derive(Default)]
+pub struct JsonRenderer {
+    rows: usize,
+}
+
+impl JsonRenderer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn write_row(&mut self, w: &mut dyn Write, json: &str) -> io::Result<()> {
+        writeln!(w, "{}", if self.rows == 0 { "[" } else { "," })?;
+        for (i, line) in json.lines().enumerate() {
+            if i > 0 {
+                writeln!(w)?;
+            }
+            write!(w, "  {}", line)?;
+        }
+
+        self.rows += 1;
+
+        Ok(())
+    }
+
+    fn write_end(&mut self, w: &mut dyn Write) -> io::Result<()> {
+        if self.rows == 0 {
+            writeln!(w, "[]")?;
+        } else {
+            writeln!(w)?;
+            writeln!(w, "]")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Renderer for JsonRenderer {
+    fn row(&mut self, w: &mut dyn Write, row: &Row) -> Result<(), CallError> {
+        let json = serde_json::to_string_pretty(row)?;
+
+        self.write_row(w, &json).map_err(CallError::Output)
+    }
+
+    fn end_document(&mut self, w: &mut dyn Write) -> Result<(), CallError> {
+        self.write_end(w).map_err(CallError::Output)
+    }
+}
@@ -0,0 +1,636 @@
+use std::fmt::Display;
+use std::io::{self, Write};
+use std::iter;
+use std::str::FromStr;
+
+use crate::data::{
+    Comment, Field, FieldState, Index, Note, Provenance, RichText, Row, SubTable, Value,
+};
+use crate::render::Renderer;
+use crate::CallError;
+
+/// How [`LatexRenderer`] typesets the table of a row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LatexOptions {
+    /// Use `longtable`, which may break across pages, instead of `tabularx`.
+    pub longtable: bool,
+    /// Rule the tables with `booktabs` (`\toprule`, `\bottomrule`) instead of
+    /// `\hline` and vertical lines.
+    pub booktabs: bool,
+    /// Width of the label column.
+    pub label_width: String,
+    /// Width of the value column.
+    pub value_width: String,
+    /// Write where the row was read as a comment before its table.
+    pub provenance: bool,
+    /// Write the comments of reviewers after the values they are on, for
+    /// draft builds.
+    pub comments: Option<CommentNotes>,
+    /// Command the footnotes and endnotes typed in cells are written with.
+    pub footnotes: FootnoteCommand,
+}
+
+/// How [`LatexRenderer`] writes the comments of reviewers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommentNotes {
+    /// `\todo[inline]` of the `todonotes` package, inline as its margin
+    /// notes cannot be placed from a table cell.
+    Todo,
+    /// `\marginnote` of the `marginnote` package.
+    Margin,
+}
+
+impl FromStr for CommentNotes {
+    type Err = String;
+
+    /// `todo` or `margin`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "todo" => Ok(Self::Todo),
+            "margin" => Ok(Self::Margin),
+            s => Err(s.to_owned()),
+        }
+    }
+}
+
+/// How [`LatexRenderer`] writes the footnotes and endnotes of values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FootnoteCommand {
+    /// `\footnote`, whose text is lost inside `tabularx` unless the table is
+    /// wrapped in a `savenotes` environment. It suits `longtable` output.
+    Footnote,
+    /// `\tablefootnote` of the `tablefootnote` package, which gets the note
+    /// out of a `tabularx` to the foot of the page. The default.
+    TableFootnote,
+}
+
+impl FromStr for FootnoteCommand {
+    type Err = String;
+
+    /// `footnote` or `tablefootnote`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "footnote" => Ok(Self::Footnote),
+            "tablefootnote" => Ok(Self::TableFootnote),
+            s => Err(s.to_owned()),
+        }
+    }
+}
+
+impl Default for LatexOptions {
+    fn default() -> Self {
+        Self {
+            longtable: false,
+            booktabs: false,
+            label_width: r".4545\linewidth".to_owned(),
+            value_width: r".4625\linewidth".to_owned(),
+            provenance: false,
+            comments: None,
+            footnotes: FootnoteCommand::TableFootnote,
+        }
+    }
+}
+
+#[derive(PartialEq, Eq)]
+enum LastItem {
+    Text,
+    Row,
+}
+
+/// Renders the catalog as a LaTeX chapter, one two column table per row.
+pub struct LatexRenderer {
+    options: LatexOptions,
+    last_item: Option<LastItem>,
+}
+
+impl LatexRenderer {
+    pub fn new(options: LatexOptions) -> Self {
+        Self {
+            options,
+            last_item: None,
+        }
+    }
+
+    fn environment(&self) -> &'static str {
+        if self.options.longtable {
+            "longtable"
+        } else {
+            "tabularx"
+        }
+    }
+}
+
+/// Labels and LaTeX values of the fields the row has, in schema order, then
+/// the extra fields labelled with their key. Text keeps the formatting typed
+/// in the cell and is followed by its footnotes, its editorial state and the
+/// comments on it when they are asked for, tables typed inside a cell come
+/// after it.
+fn fields(row: &Row, options: &LatexOptions) -> Vec<(String, String)> {
+    let rich_or = |name: &str, plain: String| match row.rich_text(name) {
+        Some(rich) => formatted(rich),
+        None => plain,
+    };
+
+    // The values of a key typed in several rows follow the first, the
+    // formatted text holds them all.
+    let values = |name: &str, value: &Value| {
+        iter::once(value)
+            .chain(row.repeated(name))
+            .map(value_latex)
+            .collect::<Vec<_>>()
+            .join("; ")
+    };
+
+    // Only a value typed as text has formatting, not a missing one.
+    let typed = |name: &str, value: &Value| match value.as_text() {
+        Some(Field::Present(_)) => rich_or(name, values(name, value)),
+        _ => values(name, value),
+    };
+
+    let extras = row.extras().iter().map(|(key, value)| {
+        let latex = with_notes(
+            rich_or(key, escape(row.joined(key, value.clone()))),
+            row.field_notes(key),
+            options.footnotes,
+        );
+        let latex = with_state(latex, row.state(key));
+        let latex = with_comments(latex, row.field_comments(key), options.comments);
+        let latex = with_sub_table(latex, row.sub_table(key));
+
+        (escape(key), latex)
+    });
+
+    row.fields()
+        .map(|(spec, value)| {
+            let latex = match value.as_text() {
+                // Each value of a key typed in several rows is indexed on its
+                // own.
+                Some(_) if spec.index => {
+                    let entries: String = iter::once(value)
+                        .chain(row.repeated(&spec.name))
+                        .filter_map(Value::as_text)
+                        .map(|text| Index::from(escape(text.as_ref()).as_str()).to_string())
+                        .collect();
+
+                    format!("{}{}", typed(&spec.name, value), entries)
+                }
+                _ => typed(&spec.name, value),
+            };
+            let latex = with_notes(latex, row.field_notes(&spec.name), options.footnotes);
+            let latex = with_state(latex, row.state(&spec.name));
+            let latex = with_comments(latex, row.field_comments(&spec.name), options.comments);
+
+            // A cell holding nothing but a table has no value of its own.
+            let latex = match row.sub_table(&spec.name) {
+                Some(table) if matches!(value.as_text(), Some(Field::Absent)) => {
+                    with_sub_table(String::new(), Some(table))
+                }
+                table => with_sub_table(latex, table),
+            };
+
+            (spec.label.clone(), latex)
+        })
+        .chain(extras)
+        .collect()
+}
+
+/// Writes the footnotes and endnotes of a value right after its text, both
+/// as footnotes of the page.
+fn with_notes<'a, I>(mut text: String, notes: I, command: FootnoteCommand) -> String
+where
+    I: Iterator<Item = &'a Note>,
+{
+    let command = match command {
+        FootnoteCommand::Footnote => r"\footnote",
+        FootnoteCommand::TableFootnote => r"\tablefootnote",
+    };
+
+    for note in notes {
+        text.push_str(&format!("{}{{{}}}", command, escape(&note.text)));
+    }
+
+    text
+}
+
+/// Notes the editorial state of a value after its text: to be verified, or
+/// the text struck out of it.
+fn with_state(mut text: String, state: Option<&FieldState>) -> String {
+    let state = match state {
+        Some(state) => state,
+        None => return text,
+    };
+
+    if state.to_verify {
+        text.push_str(r" \textit{(a conferir)}");
+    }
+
+    if let Some(removed) = state.removed.as_ref() {
+        text.push_str(&format!(r" \textit{{(riscado: {})}}", escape(removed)));
+    }
+
+    text
+}
+
+/// Writes the comments on a value after its text as draft notes.
+fn with_comments<'a, I>(mut text: String, comments: I, notes: Option<CommentNotes>) -> String
+where
+    I: Iterator<Item = &'a Comment>,
+{
+    let command = match notes {
+        Some(CommentNotes::Todo) => r"\todo[inline]",
+        Some(CommentNotes::Margin) => r"\marginnote",
+        None => return text,
+    };
+
+    for comment in comments {
+        let note = match comment.author.as_ref() {
+            Some(author) => format!("{}: {}", author, comment.text),
+            None => comment.text.clone(),
+        };
+
+        text.push_str(&format!(" {}{{{}}}", command, escape(note)));
+    }
+
+    text
+}
+
+/// Typesets a table typed inside a cell after the text of the cell: a table
+/// of a single column as a list, other tables as an inner `tabular`.
+fn with_sub_table(text: String, table: Option<&SubTable>) -> String {
+    let table = match table {
+        Some(table) => table,
+        None => return text,
+    };
+
+    let inner = if table.is_list() {
+        let items: String = table
+            .lines()
+            .iter()
+            .map(|line| format!(r" \item {}", escape(line.join(" "))))
+            .collect();
+
+        format!(r"\begin{{itemize}}{} \end{{itemize}}", items)
+    } else {
+        let columns = table.lines().iter().map(Vec::len).max().unwrap_or(0);
+        let lines = table
+            .lines()
+            .iter()
+            .map(|line| line.iter().map(escape).collect::<Vec<_>>().join(" & "))
+            .collect::<Vec<_>>()
+            .join(r" \\ ");
+
+        format!(
+            r"\begin{{tabular}}{{{}}} {} \end{{tabular}}",
+            "l".repeat(columns),
+            lines
+        )
+    };
+
+    if text.is_empty() {
+        inner
+    } else if table.is_list() {
+        format!("{} {}", text, inner)
+    } else {
+        format!(r"{} \par{}", text, inner)
+    }
+}
+
+/// The tables a row was read from, each with the span of rows, as in
+/// `a.docx, table 3 (Tabela 57), rows 1-8`.
+fn sources(row: &Row) -> Vec<String> {
+    let mut tables: Vec<(&Provenance, usize, usize)> = Vec::new();
+
+    for p in row.provenances() {
+        let same_table =
+            |(t, _, _): &&mut (&Provenance, usize, usize)| t.file == p.file && t.table == p.table;
+
+        match tables.iter_mut().find(same_table) {
+            Some((_, first, last)) => {
+                *first = p.row.min(*first);
+                *last = p.row.max(*last);
+            }
+            None => tables.push((p, p.row, p.row)),
+        }
+    }
+
+    tables
+        .into_iter()
+        .map(|(p, first, last)| {
+            let mut source = String::new();
+
+            if let Some(file) = p.file.as_ref() {
+                source.push_str(&format!("{}, ", file.display()));
+            }
+
+            source.push_str(&format!("table {}", p.table));
+
+            if let Some(caption) = p.caption.as_ref() {
+                source.push_str(&format!(" ({})", caption));
+            }
+
+            if first == last {
+                source.push_str(&format!(", row {}", first));
+            } else {
+                source.push_str(&format!(", rows {}-{}", first, last));
+            }
+
+            source
+        })
+        .collect()
+}
+
+/// Typesets formatted text, each span in the commands of its formatting. The
+/// spaces around a span are left out of the commands.
+fn formatted(text: &RichText) -> String {
+    text.spans()
+        .iter()
+        .map(|span| {
+            let words = span.text.trim();
+            let start = span.text.len() - span.text.trim_start().len();
+            let (before, after) = (&span.text[..start], &span.text[start + words.len()..]);
+
+            let mut latex = escape(words);
+            let style = span.style;
+
+            for (on, command) in [
+                (style.superscript, "textsuperscript"),
+                (style.small_caps, "textsc"),
+                (style.italic, "textit"),
+                (style.bold, "textbf"),
+            ] {
+                if on {
+                    latex = format!(r"\{}{{{}}}", command, latex);
+                }
+            }
+
+            format!("{}{}{}", before, latex, after)
+        })
+        .collect()
+}
+
+/// Typesets a value: text and values that could not be read are escaped,
+/// the others are written in the form of their field.
+fn value_latex(value: &Value) -> String {
+    match value {
+        Value::Text(_) => escape(value),
+        // Values that could not be read are kept as typed.
+        _ if value.is_not_standard() => escape(value),
+        _ => value.to_string(),
+    }
+}
+
+/// Escapes the characters of typed text that LaTeX would read as markup.
+fn escape<T: Display>(text: T) -> String {
+    let text = text.to_string();
+    let mut latex = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '\\' => latex.push_str(r"\textbackslash{}"),
+            '~' => latex.push_str(r"\textasciitilde{}"),
+            '^' => latex.push_str(r"\textasciicircum{}"),
+            '$' | '&' | '%' | '#' | '_' | '{' | '}' => {
+                latex.push('\\');
+                latex.push(c);
+            }
+            c => latex.push(c),
+        }
+    }
+
+    latex
+}
+
+impl LatexRenderer {
+    fn write_start(&mut self, w: &mut dyn Write, title: &str) -> io::Result<()> {
+        writeln!(w, r"\chapter{{{}}}", title)?;
+        writeln!(w)?;
+
+        Ok(())
+    }
+
+    fn write_section(&mut self, w: &mut dyn Write, heading: &str) -> io::Result<()> {
+        if self.last_item.is_some() {
+            writeln!(w)?;
+        }
+        writeln!(w, r"\section{{{}}}", heading)?;
+        writeln!(w)?;
+
+        self.last_item = None;
+
+        Ok(())
+    }
+
+    fn write_row(&mut self, w: &mut dyn Write, row: &Row) -> io::Result<()> {
+        if self.last_item.is_some() {
+            writeln!(w)?;
+            writeln!(w, r"\bigskip")?;
+            writeln!(w)?;
+        }
+
+        if self.options.provenance {
+            for source in sources(row) {
+                writeln!(w, "% {}", source)?;
+            }
+        }
+
+        let options = &self.options;
+        let (rule, columns) = if options.booktabs {
+            (
+                None,
+                format!("p{{{}}} p{{{}}}", options.label_width, options.value_width),
+            )
+        } else {
+            (
+                Some(r"\hline"),
+                format!(
+                    "| p{{{}}} | p{{{}}} |",
+                    options.label_width, options.value_width
+                ),
+            )
+        };
+
+        write!(w, r"\noindent\begin{{{}}}", self.environment())?;
+        if !options.longtable {
+            write!(w, r"{{\linewidth}}")?;
+        }
+        writeln!(w, "{{{}}}", columns)?;
+        writeln!(w, "    {}", rule.unwrap_or(r"\toprule"))?;
+
+        let fields = fields(row, &self.options);
+        for (i, (label, value)) in fields.iter().enumerate() {
+            writeln!(w, r"    \hfill {} & {} \\", label, value)?;
+
+            match rule {
+                Some(rule) => writeln!(w, "    {}", rule)?,
+                None if i + 1 == fields.len() => writeln!(w, r"    \bottomrule")?,
+                None => {}
+            }
+        }
+
+        if fields.is_empty() && rule.is_none() {
+            writeln!(w, r"    \bottomrule")?;
+        }
+
+        writeln!(w, r"\end{{{}}}", self.environment())?;
+
+        self.last_item = Some(LastItem::Row);
+
+        Ok(())
+    }
+
+    fn write_paragraph(&mut self, w: &mut dyn Write, text: &str) -> io::Result<()> {
+        if self.last_item == Some(LastItem::Row) {
+            writeln!(w)?;
+        }
+
+        writeln!(w, "{}", escape(text))?;
+
+        self.last_item = Some(LastItem::Text);
+
+        Ok(())
+    }
+}
+
+impl Renderer for LatexRenderer {
+    fn start_document(&mut self, w: &mut dyn Write, title: &str) -> Result<(), CallError> {
+        self.write_start(w, title).map_err(CallError::Output)
+    }
+
+    fn section(&mut self, w: &mut dyn Write, heading: &str) -> Result<(), CallError> {
+        self.write_section(w, heading).map_err(CallError::Output)
+    }
+
+    fn row(&mut self, w: &mut dyn Write, row: &Row) -> Result<(), CallError> {
+        self.write_row(w, row).map_err(CallError::Output)
+    }
+
+    fn paragraph(&mut self, w: &mut dyn Write, text: &str) -> Result<(), CallError> {
+        self.write_paragraph(w, text).map_err(CallError::Output)
+    }
+}
+
+#[test]
+fn write_errors() {
+    struct Closed;
+
+    impl Write for Closed {
+        fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+            Err(io::ErrorKind::BrokenPipe.into())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let mut renderer = LatexRenderer::new(LatexOptions::default());
+    let e = renderer
+        .start_document(&mut Closed, "Catálogo")
+        .unwrap_err();
+
+    assert!(matches!(e, CallError::Output(_)));
+    assert!(e.to_string().starts_with("could not write the output"));
+}
+
+#[test]
+fn special_characters() {
+    use crate::data::{NoteKind, Schema};
+    use std::rc::Rc;
+
+    assert_eq!(escape("R$ 5"), r"R\$ 5");
+    assert_eq!(
+        escape(r"50% & #1_a {b} ~ ^ \"),
+        r"50\% \& \#1\_a \{b\} \textasciitilde{} \textasciicircum{} \textbackslash{}"
+    );
+
+    let mut row = Row::new(Rc::new(Schema::default()));
+    let ata = ("Número e data da Ata de sessão", "sem ata & data");
+    row.set_field((ata.0.to_owned(), ata.1.to_owned()))
+        .unwrap_err();
+    row.set_field(("Quem doou?".to_owned(), "Lyra & Filhos".to_owned()))
+        .unwrap();
+    row.set_field(("Preço".to_owned(), "10% de 5$".to_owned()))
+        .unwrap();
+
+    row.set_field(("Página".to_owned(), "p.7".to_owned()))
+        .unwrap();
+    row.annotate(
+        "page",
+        FieldState {
+            to_verify: false,
+            removed: Some("p.7_b".to_owned()),
+        },
+    );
+    row.add_comment(
+        "page",
+        Comment {
+            author: Some("M. & Filhos".to_owned()),
+            date: None,
+            text: "ver {ata} #3".to_owned(),
+        },
+    );
+    row.add_note(
+        "page",
+        Note {
+            kind: NoteKind::Footnote,
+            text: "custou 5$ ~ 10%".to_owned(),
+        },
+    );
+
+    let mut latex = Vec::new();
+    let mut renderer = LatexRenderer::new(LatexOptions {
+        comments: Some(CommentNotes::Todo),
+        ..LatexOptions::default()
+    });
+    renderer.row(&mut latex, &row).unwrap();
+    renderer.paragraph(&mut latex, "Tabela #2").unwrap();
+    let latex = String::from_utf8(latex).unwrap();
+
+    assert!(latex.contains(r"& sem ata \& data \\"));
+    assert!(latex.contains(r"& Lyra \& Filhos\index{Lyra!\& Filhos} \\"));
+    assert!(latex.contains(r"\hfill Preço & 10\% de 5\$ \\"));
+    assert!(latex.contains(r"Tabela \#2"));
+    assert!(latex.contains(r"\tablefootnote{custou 5\$ \textasciitilde{} 10\%}"));
+    assert!(latex.contains(r"\textit{(riscado: p.7\_b)}"));
+    assert!(latex.contains(r"\todo[inline]{M. \& Filhos: ver \{ata\} \#3}"));
+}
+
+#[test]
+fn provenance_comments() {
+    use crate::data::Schema;
+    use std::rc::Rc;
+
+    let source = |table, row, caption: Option<&str>| Provenance {
+        file: Some("a.docx".into()),
+        table,
+        row,
+        caption: caption.map(str::to_owned),
+        raw: String::new(),
+    };
+
+    // A record going on over two tables.
+    let mut row = Row::new(Rc::new(Schema::default()));
+    for (key, value, source) in [
+        ("Ano da Revista", "1905", source(3, 1, Some("Tabela 57"))),
+        ("Página", "p.3", source(3, 2, Some("Tabela 57"))),
+        ("Quem doou?", "Abel", source(4, 1, None)),
+    ] {
+        row.set_field_from((key.to_owned(), value.to_owned()), Some(source))
+            .unwrap();
+    }
+
+    let render = |provenance| {
+        let mut latex = Vec::new();
+        let mut renderer = LatexRenderer::new(LatexOptions {
+            provenance,
+            ..LatexOptions::default()
+        });
+        renderer.row(&mut latex, &row).unwrap();
+        String::from_utf8(latex).unwrap()
+    };
+
+    assert!(render(true).starts_with(
+        "% a.docx, table 3 (Tabela 57), rows 1-2\n% a.docx, table 4, row 1\n\\noindent"
+    ));
+    assert!(render(false).starts_with(r"\noindent"));
+}
@@ -0,0 +1,54 @@
+//! Parsing of the catalog tables typed in Word or LibreOffice documents.
+//!
+//! Each record of the catalog is a two column table (key, value) in the
+//! document, or a row of a grid whose first row holds the keys as headings.
+//! [`ParseXml`] walks the document XML and turns the records of its tables
+//! into [`Row`](data::Row)s, keeping the text found between tables as
+//! paragraphs.
+//! Records kept in spreadsheets, one per sheet row, are read by
+//! [`read_spreadsheet`], and rows are exchanged as CSV or TSV with
+//! [`write_delimited`] and [`read_delimited`] or exported as JSON with
+//! [`write_json`].
+//!
+//! The fields of a row, the table keys that name them and how their values
+//! are read are declared by a [`Schema`](data::Schema), loaded from a TOML or
+//! JSON file or defaulting to the catalog of periodicals.
+//!
+//! Output formats implement [`Renderer`]; [`LatexRenderer`] typesets the
+//! catalog itself and [`ReviewRenderer`] lists the comments reviewers left
+//! on it.
+
+mod annotation;
+pub mod data;
+mod delimited;
+mod diagnostic;
+mod error;
+mod format;
+mod input;
+mod json;
+mod latex;
+mod parse;
+mod reader;
+mod records;
+mod render;
+mod review;
+mod revision;
+mod spreadsheet;
+
+pub use crate::annotation::{AnnotationRules, Mark, MarkMeaning};
+pub use crate::delimited::{read_delimited, write_delimited, DelimitedRenderer};
+pub use crate::diagnostic::{Location, Warning};
+pub use crate::error::CallError;
+pub use crate::format::Format;
+pub use crate::input::{is_supported, read_path, Items};
+pub use crate::json::{write_json, JsonRenderer};
+pub use crate::latex::{CommentNotes, FootnoteCommand, LatexOptions, LatexRenderer};
+pub use crate::parse::ParseError;
+pub use crate::reader::{
+    read_docx, read_document, read_odt, ParseXml, ReadOptions, RecordBoundary, RowOrParagraph, Rows,
+    TableLayout,
+};
+pub use crate::render::Renderer;
+pub use crate::review::ReviewRenderer;
+pub use crate::revision::{Revision, RevisionKind, RevisionMode};
+pub use crate::spreadsheet::read_spreadsheet;
@@ -1,236 +1,316 @@
-use crate::parse::{ParseError, ParseStatus};
-use data::Row;
-use regex::Regex;
-use std::io::{BufReader, Error as IoError, Read};
-use std::{env, fs::File};
-use xml::reader::{Error as XmlError, EventReader, XmlEvent};
-use zip::result::ZipError;
-use zip::ZipArchive;
-
-mod data;
-mod parse;
+use crate::options::{ArgError, Options, SortKey};
+use parse_tables::data::{Range, SchemaError};
+use parse_tables::{read_path, CallError, Items, ReadOptions, RecordBoundary, RowOrParagraph};
+use std::fmt::Display;
+use std::io::{self, Error as IoError};
+use std::path::{Path, PathBuf};
+use std::{env, process};
+
+mod options;
 
 #[derive(Debug)]
-enum CallError {
-    NoArgument,
+enum Error {
+    Args(ArgError),
     Io(IoError),
-    Xml(XmlError),
-    Parse(ParseError),
-    Zip(ZipError),
-    AlreadyFinished,
-    Paragraph(String),
+    Schema(SchemaError),
+    Document(PathBuf, CallError),
+    Output(CallError),
 }
 
-impl From<IoError> for CallError {
-    fn from(e: IoError) -> Self {
-        CallError::Io(e)
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Args(e) => write!(f, "{}", e),
+            Error::Io(e) => write!(f, "{}", e),
+            Error::Schema(e) => write!(f, "invalid schema: {}", e),
+            Error::Document(path, e) => write!(f, "{}: {}", path.display(), e),
+            Error::Output(e) => write!(f, "{}", e),
+        }
     }
 }
 
-impl From<XmlError> for CallError {
-    fn from(e: XmlError) -> Self {
-        CallError::Xml(e)
+impl From<ArgError> for Error {
+    fn from(e: ArgError) -> Self {
+        Error::Args(e)
     }
 }
 
-impl From<ParseError> for CallError {
-    fn from(e: ParseError) -> Self {
-        CallError::Parse(e)
+impl From<IoError> for Error {
+    fn from(e: IoError) -> Self {
+        Error::Io(e)
     }
 }
 
-impl From<ZipError> for CallError {
-    fn from(e: ZipError) -> Self {
-        CallError::Zip(e)
+fn main() {
+    if let Err(e) = run() {
+        eprintln!("error: {}", e);
+        process::exit(1);
     }
 }
 
-struct ParseXml<R: Read> {
-    parser: EventReader<R>,
-    status: ParseStatus,
-    row: Option<Row>,
-}
-
-impl<R: Read> ParseXml<R> {
-    fn new(r: R) -> Self {
-        Self {
-            parser: EventReader::new(r),
-            status: ParseStatus::SearchingTable,
-            row: None,
-        }
-    }
+fn run() -> Result<(), Error> {
+    let options = Options::from_args(env::args().skip(1))?;
 
-    fn next(&mut self) -> Result<Row, CallError> {
-        let status = &mut self.status;
+    let schema = options.load_schema().map_err(Error::Schema)?;
 
-        if status.is_finished() {
-            return self.row.take().ok_or(CallError::AlreadyFinished);
+    if let SortKey::Field(name) = &options.sort {
+        if schema.position(name).is_none() {
+            return Err(ArgError::InvalidArgument(name.clone()).into());
         }
+    }
 
-        let mut row = self.row.take().unwrap_or_else(Row::new);
-
-        loop {
-            match self.parser.next() {
-                Ok(XmlEvent::StartElement {
-                    name, attributes, ..
-                }) => match name.local_name.as_str() {
-                    "tbl" => status.start_table()?,
-                    "tr" => status.start_field()?,
-                    "tc" => status.start_col()?,
-                    "p" => {
-                        status.append_new_line().ok();
-                    }
-                    "t" => {
-                        if let Some(att) =
-                            attributes.iter().find(|att| att.name.local_name == "space")
-                        {
-                            if att.value == "preserve" {
-                                status
-                                    .append_text(" ")
-                                    .map_err(|_| CallError::Paragraph(" ".to_owned()))?;
-                            }
-                        }
-                    }
-                    _ => {}
-                },
-                Ok(XmlEvent::EndElement { name, .. }) => match name.local_name.as_str() {
-                    "tc" => status.end_col()?,
-                    "tr" => {
-                        let f = status.end_field()?;
-
-                        if let Err(f) = row.set_field(f) {
-                            let mut new_row = Row::new();
-                            new_row.set_field(f).ok();
-                            self.row = Some(new_row);
-
-                            return Ok(row);
-                        }
-                    }
-                    "tbl" => {
-                        status.end_table()?;
-                        return Ok(row);
-                    }
-                    _ => {}
-                },
-                Ok(XmlEvent::Characters(text)) => {
-                    status
-                        .append_text(&text)
-                        .map_err(|_| CallError::Paragraph(text))?;
-                }
-                Ok(XmlEvent::EndDocument) => {
-                    return match status.finish() {
-                        Ok(_) => Err(CallError::AlreadyFinished),
-                        Err(f) => {
-                            row.set_field(f).unwrap_or_else(|f| {
-                                let mut new_row = Row::new();
-                                new_row.set_field(f).ok();
-                                self.row = Some(new_row);
-                            });
-
-                            Ok(row)
-                        }
-                    };
-                }
-                Err(e) => {
-                    status.finish().ok();
-                    return Err(e.into());
-                }
-                _ => {}
-            }
+    if let RecordBoundary::Key(name) = &options.boundary {
+        if schema.position(name).is_none() {
+            return Err(ArgError::InvalidArgument(name.clone()).into());
         }
     }
-}
 
-enum RowOrParagraph {
-    Row(Row),
-    Paragraph(String),
-}
+    let read_options = ReadOptions {
+        schema: schema.clone(),
+        boundary: options.boundary.clone(),
+        layout: options.layout,
+        annotations: options.annotations.clone(),
+        revisions: options.revisions,
+        file: None,
+    };
 
-impl<R: Read> IntoIterator for ParseXml<R> {
-    type Item = RowOrParagraph;
+    let mut items = Vec::new();
 
-    type IntoIter = Rows<R>;
+    for path in options.input_files()? {
+        let rows = read_path(&path, &read_options).map_err(|e| Error::Document(path.clone(), e))?;
 
-    fn into_iter(self) -> Self::IntoIter {
-        Rows { parser: self }
+        collect(&path, rows, options.strict, &mut items)?;
     }
-}
-
-struct Rows<R: Read> {
-    parser: ParseXml<R>,
-}
-
-impl<R: Read> Iterator for Rows<R> {
-    type Item = RowOrParagraph;
 
-    fn next(&mut self) -> Option<Self::Item> {
-        loop {
-            match self.parser.next() {
-                Ok(row) => break Some(RowOrParagraph::Row(row)),
-                Err(CallError::Paragraph(p)) => break Some(RowOrParagraph::Paragraph(p)),
-                Err(CallError::AlreadyFinished) => break None,
-                _ => {}
+    if options.sort != SortKey::None {
+        // Paragraphs only make sense next to the tables around them, so a
+        // sorted catalog is made of rows alone.
+        let mut rows = Vec::new();
+        let mut paragraphs = 0;
+
+        for item in items {
+            match item {
+                RowOrParagraph::Row(row) => rows.push(row),
+                RowOrParagraph::Paragraph(_) => paragraphs += 1,
+                RowOrParagraph::Warning(_) => {}
             }
         }
-    }
-}
 
-fn main() -> Result<(), CallError> {
-    let path = env::args().skip(1).next().ok_or(CallError::NoArgument)?;
-    // let path = "/home/gabriel/Downloads/Tabelas completas livro de bruno(3).docx";
-    let mut zip = ZipArchive::new(File::open(path)?)?;
+        if paragraphs > 0 {
+            eprintln!(
+                "warning: a sorted catalog holds rows only, {} paragraphs between tables were left out",
+                paragraphs
+            );
+        }
 
-    let entry = BufReader::new(zip.by_name("word/document.xml")?);
+        // Rows are grouped by year first so that every year gets a single
+        // section, and ordered by the selected key inside it.
+        rows.sort_by(|lhs, rhs| {
+            lhs.section_year()
+                .cmp(&rhs.section_year())
+                .then_with(|| options.sort.compare(lhs, rhs))
+        });
+
+        items = rows.into_iter().map(RowOrParagraph::Row).collect();
+    } else {
+        items = group_by_section(items);
+    }
 
-    println!(r"\chapter{{Catálogo}}");
-    println!();
-    println!(r"\section{{Revistas publicadas em 1903}}");
-    println!();
+    let stdout = io::stdout();
+    let mut w = stdout.lock();
+    let mut renderer = options.renderer(&schema);
 
-    #[derive(PartialEq, Eq)]
-    enum LastItem {
-        Text,
-        Row,
-    }
+    renderer
+        .start_document(&mut w, &options.chapter)
+        .map_err(Error::Output)?;
 
-    let mut last_item = None;
+    let mut section = None;
+    let years = section_years(&items);
 
-    let number_regex = Regex::new(r"^\d+$").unwrap();
-    let tabela_regex = Regex::new(r"^Tabela \d+$").unwrap();
+    for (row_or_p, year) in items.into_iter().zip(years) {
+        if let Some(year) = year {
+            if section.as_ref() != Some(&year) {
+                renderer
+                    .section(&mut w, &options.section_heading(year.as_ref()))
+                    .map_err(Error::Output)?;
+
+                section = Some(year);
+            }
+        }
 
-    for row_or_p in ParseXml::new(entry).into_iter() {
         match row_or_p {
             RowOrParagraph::Row(row) => {
-                if last_item.is_some() {
-                    println!();
-                    println!(r"\bigskip");
-                    println!();
-                }
-                println!(r"\noindent{}", row);
-                last_item = Some(LastItem::Row);
+                renderer.row(&mut w, &row).map_err(Error::Output)?;
             }
             RowOrParagraph::Paragraph(p) => {
-                let trimmed = p.trim();
-                match trimmed {
-                    "Tabela" | r"SEQ Tabela \* ARABIC" => continue,
-                    _ => (),
-                };
-
-                if number_regex.is_match(trimmed) || tabela_regex.is_match(trimmed) {
-                    continue;
-                }
-
-                if Some(LastItem::Row) == last_item {
-                    println!();
-                    eprintln!();
-                }
                 eprintln!("paragraph: {}", p);
 
-                println!("{}", p.replace("$", r"\$"));
-                last_item = Some(LastItem::Text);
+                renderer.paragraph(&mut w, &p).map_err(Error::Output)?;
             }
+            RowOrParagraph::Warning(_) => {}
+        }
+    }
+
+    renderer.end_document(&mut w).map_err(Error::Output)?;
+
+    Ok(())
+}
+
+/// Gathers the rows and paragraphs read from a file, reporting its warnings.
+/// A malformed table stops the reading in strict mode, otherwise it is
+/// reported and the reading resumes at the next table.
+fn collect(
+    path: &Path,
+    rows: Items,
+    strict: bool,
+    items: &mut Vec<RowOrParagraph>,
+) -> Result<(), Error> {
+    for item in rows {
+        match item {
+            Ok(RowOrParagraph::Warning(w)) => eprintln!("{}: warning: {}", path.display(), w),
+            Ok(item) => items.push(item),
+            Err(e) if strict => return Err(Error::Document(path.to_owned(), e)),
+            Err(e) => eprintln!("{}: error: {}, {}", path.display(), e, what_follows(&e)),
         }
     }
 
     Ok(())
 }
+
+/// What is done after an error of a file read in lenient mode: a malformed
+/// table is skipped, while other errors, malformed XML first, end the reading
+/// of the file.
+fn what_follows(e: &CallError) -> &'static str {
+    match e {
+        CallError::Parse(_) => "skipping to the next table",
+        _ => "the rest of the file was not read",
+    }
+}
+
+/// Gathers the items of each section, so that a year coming back later in
+/// the documents does not open a second section of the same name. Sections
+/// keep the order in which their year first appears and paragraphs go with
+/// the row after them, as captions and headings come before their table.
+fn group_by_section(items: Vec<RowOrParagraph>) -> Vec<RowOrParagraph> {
+    let mut sections: Vec<(Option<Range>, Vec<RowOrParagraph>)> = Vec::new();
+    let mut pending = Vec::new();
+    let mut last = None;
+
+    for item in items {
+        let year = match &item {
+            RowOrParagraph::Row(row) => row.section_year(),
+            _ => {
+                pending.push(item);
+                continue;
+            }
+        };
+
+        let i = match sections.iter().position(|(y, _)| *y == year) {
+            Some(i) => i,
+            None => {
+                sections.push((year, Vec::new()));
+                sections.len() - 1
+            }
+        };
+
+        sections[i].1.append(&mut pending);
+        sections[i].1.push(item);
+        last = Some(i);
+    }
+
+    // Paragraphs after the last row stay with it.
+    match last {
+        Some(i) => sections[i].1.append(&mut pending),
+        None => return pending,
+    }
+
+    sections.into_iter().flat_map(|(_, items)| items).collect()
+}
+
+/// The section of each item: the year of a row, and for a paragraph the year
+/// of the row after it, so that a caption opening a section is written after
+/// its heading. Paragraphs after the last row have none and stay in the
+/// section they are in.
+fn section_years(items: &[RowOrParagraph]) -> Vec<Option<Option<Range>>> {
+    let mut years = vec![None; items.len()];
+    let mut next = None;
+
+    for (item, year) in items.iter().zip(years.iter_mut()).rev() {
+        if let RowOrParagraph::Row(row) = item {
+            next = Some(row.section_year());
+        }
+
+        *year = next.clone();
+    }
+
+    years
+}
+
+#[test]
+fn strict_mode() {
+    use parse_tables::ParseXml;
+    use std::io::Cursor;
+
+    let document =
+        "<w:document xmlns:w=\"http://schemas.openxmlformats.org/wordprocessingml/2006/main\">\
+         <w:body><w:tbl><w:tr><w:tc><w:p><w:r><w:t>Ano da Revista</w:t></w:r></w:p></w:tc>\
+         <w:tc><w:p><w:r><w:t>1905</w:t></w:r></w:p></w:tc><w:tc><w:p/></w:tc></w:tr></w:tbl>\
+         <w:tbl><w:tr><w:tc><w:p><w:r><w:t>Ano da Revista</w:t></w:r></w:p></w:tc>\
+         <w:tc><w:p><w:r><w:t>1906</w:t></w:r></w:p></w:tc></w:tr></w:tbl></w:body></w:document>";
+    let rows = || -> Items { Box::new(ParseXml::new(Cursor::new(document)).into_iter()) };
+    let path = Path::new("catalogo.docx");
+
+    let mut items = Vec::new();
+    collect(path, rows(), false, &mut items).unwrap();
+    assert!(matches!(&items[..], [RowOrParagraph::Row(_)]));
+
+    let mut items = Vec::new();
+    assert!(matches!(
+        collect(path, rows(), true, &mut items),
+        Err(Error::Document(..))
+    ));
+    assert!(items.is_empty());
+
+    // A stray `&` ends the reading of the file.
+    let document = document.replace("1906", "1906 & 1907");
+    let errors: Vec<_> = ParseXml::new(Cursor::new(&document))
+        .into_iter()
+        .filter_map(Result::err)
+        .collect();
+
+    match &errors[..] {
+        [first, second, rest @ ..] => {
+            assert_eq!(what_follows(first), "skipping to the next table");
+            assert_eq!(what_follows(second), "the rest of the file was not read");
+            assert!(rest.is_empty());
+        }
+        _ => panic!("{} errors", errors.len()),
+    }
+}
+
+#[test]
+fn section_of_paragraphs() {
+    use parse_tables::data::Row;
+
+    let row = |year: &str| {
+        let mut row = Row::default();
+        row.set_field(("Ano da Revista".to_owned(), year.to_owned()))
+            .unwrap();
+        RowOrParagraph::Row(row)
+    };
+    let paragraph = |text: &str| RowOrParagraph::Paragraph(text.to_owned());
+    let year = |year: &str| Some(Some(year.parse::<Range>().unwrap()));
+
+    let items = group_by_section(vec![
+        paragraph("Tabela 1"),
+        row("1903"),
+        paragraph("Tabela 2"),
+        row("1904"),
+        paragraph("Fim"),
+    ]);
+
+    assert_eq!(
+        section_years(&items),
+        [year("1903"), year("1903"), year("1904"), year("1904"), None]
+    );
+}
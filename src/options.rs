@@ -0,0 +1,374 @@
+use std::{
+    cmp::Ordering,
+    fmt::Display,
+    fs,
+    io::Error as IoError,
+    path::{Path, PathBuf},
+    rc::Rc,
+    str::FromStr,
+};
+
+use parse_tables::data::{Range, Row, Schema, SchemaError};
+use parse_tables::{
+    is_supported, AnnotationRules, DelimitedRenderer, JsonRenderer, LatexOptions, LatexRenderer,
+    Mark, MarkMeaning, RecordBoundary, Renderer, ReviewRenderer, RevisionMode, TableLayout,
+};
+
+#[derive(Debug)]
+pub enum ArgError {
+    NoArgument,
+    MissingValue(String),
+    InvalidArgument(String),
+}
+
+impl Display for ArgError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ArgError::NoArgument => write!(f, "{}", USAGE),
+            ArgError::MissingValue(arg) => write!(f, "option {} needs a value", arg),
+            ArgError::InvalidArgument(arg) => write!(f, "invalid argument {:?}", arg),
+        }
+    }
+}
+
+const USAGE: &str = "usage: parse-tables [options] <input or directory>...
+
+inputs are .docx and .odt documents, spreadsheets (.xlsx, .ods, ...) and
+.csv or .tsv files written with --format
+
+options:
+    -s, --sort <key>            none, row or the name of a field: year,
+                                number-volume, ata, page, kind, title, doner
+                                or time with the default schema
+    -f, --format <format>       latex (default), csv, tsv, json or review (the
+                                comments of reviewers, one per line)
+    --schema <file>             fields of the catalog, a .toml or .json file
+    --records <boundary>        what ends a record in the document tables:
+                                duplicate (a repeated key, default), table
+                                (a repeated key adds to its field), blank (an
+                                empty row) or key:<field> (the key of the
+                                field starts a record, another repeated key
+                                adds to its field)
+    --tables <layout>           layout of the document tables: auto (default),
+                                key-value or grid (a heading row, then one
+                                record per row)
+    --mark <mark>=<meaning>     what a mark typed on values means, for the
+                                marks highlight (verify by default), shading
+                                (ignore) and strike (remove): verify, remove
+                                (left out of the value, kept as a note) or
+                                ignore
+    --revisions <mode>          what is done with the revisions still tracked
+                                in Word documents: accept (default), reject
+                                or fail (refuse the document, listing them)
+    --strict                    stop at the first malformed table
+    --lenient                   report a malformed table and go on with the
+                                next one (default)
+    --chapter <title>           title of the catalog chapter
+    --section <template>        section heading, {year} is replaced by the year
+    --undated-section <title>   heading of the rows without a year
+    --longtable                 typeset rows with longtable instead of tabularx
+    --booktabs                  rule the tables with booktabs
+    --column-widths <l>,<v>     widths of the label and value columns
+    --provenance                comment each table with where it was read
+    --comments <notes>          write the comments of reviewers as notes: todo
+                                (todonotes) or margin (marginnote)
+    --footnotes <command>       command of the footnotes and endnotes of
+                                values: tablefootnote (default, needs the
+                                tablefootnote package) or footnote (lost
+                                in tables unless --longtable is given)";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SortKey {
+    None,
+    Row,
+    /// Name of a field of the schema.
+    Field(String),
+}
+
+impl FromStr for SortKey {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "" => Err(s.to_owned()),
+            "none" => Ok(Self::None),
+            "row" => Ok(Self::Row),
+            s => Ok(Self::Field(s.replace('-', "_"))),
+        }
+    }
+}
+
+impl SortKey {
+    /// Compares two rows by the selected field, falling back to the whole row
+    /// order (`Row`'s `Ord`) so ties are still deterministic. The first
+    /// section field compares by the section year, so rows without one fall
+    /// back to the year of the other section fields (the ata year for the
+    /// periodicals).
+    pub fn compare(&self, lhs: &Row, rhs: &Row) -> Ordering {
+        let by_key = match self {
+            SortKey::None | SortKey::Row => Ordering::Equal,
+            SortKey::Field(name) if is_section_year(lhs, name) => {
+                lhs.section_year().cmp(&rhs.section_year())
+            }
+            SortKey::Field(name) => lhs.get(name).cmp(&rhs.get(name)),
+        };
+
+        by_key.then_with(|| lhs.cmp(rhs))
+    }
+}
+
+/// Whether `name` is the first section field of the schema of the row, the
+/// one [`Row::section_year`] reads first.
+fn is_section_year(row: &Row, name: &str) -> bool {
+    row.schema()
+        .fields()
+        .iter()
+        .find(|f| f.section)
+        .is_some_and(|f| f.name == name)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Latex,
+    Csv,
+    Tsv,
+    Json,
+    Review,
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "latex" | "tex" => Ok(Self::Latex),
+            "csv" => Ok(Self::Csv),
+            "tsv" => Ok(Self::Tsv),
+            "json" => Ok(Self::Json),
+            "review" => Ok(Self::Review),
+            s => Err(s.to_owned()),
+        }
+    }
+}
+
+pub struct Options {
+    pub inputs: Vec<PathBuf>,
+    pub sort: SortKey,
+    pub schema: Option<PathBuf>,
+    pub boundary: RecordBoundary,
+    pub layout: TableLayout,
+    pub annotations: AnnotationRules,
+    pub revisions: RevisionMode,
+    pub strict: bool,
+    pub output: OutputFormat,
+    pub chapter: String,
+    pub section: String,
+    pub undated_section: String,
+    pub latex: LatexOptions,
+}
+
+impl Options {
+    pub fn from_args<I: Iterator<Item = String>>(mut args: I) -> Result<Self, ArgError> {
+        let mut inputs = Vec::new();
+        let mut sort = None;
+        let mut schema = None;
+        let mut boundary = RecordBoundary::DuplicateKey;
+        let mut layout = TableLayout::Auto;
+        let mut annotations = AnnotationRules::default();
+        let mut revisions = RevisionMode::Accept;
+        let mut strict = false;
+        let mut output = OutputFormat::Latex;
+        let mut chapter = "Catálogo".to_owned();
+        let mut section = "Revistas publicadas em {year}".to_owned();
+        let mut undated_section = "Revistas sem data de publicação".to_owned();
+        let mut latex = LatexOptions::default();
+
+        while let Some(arg) = args.next() {
+            let (name, inline_value) = match arg.find('=') {
+                Some(i) if arg.starts_with("--") => (&arg[..i], Some(arg[i + 1..].to_owned())),
+                _ => (arg.as_str(), None),
+            };
+
+            let mut value = || {
+                inline_value
+                    .clone()
+                    .or_else(|| args.next())
+                    .ok_or_else(|| ArgError::MissingValue(name.to_owned()))
+            };
+
+            match name {
+                "--sort" | "-s" => {
+                    sort = Some(value()?.parse().map_err(ArgError::InvalidArgument)?);
+                }
+                "--format" | "-f" => {
+                    output = value()?.parse().map_err(ArgError::InvalidArgument)?;
+                }
+                "--schema" => schema = Some(PathBuf::from(value()?)),
+                "--records" => {
+                    boundary = value()?.parse().map_err(ArgError::InvalidArgument)?;
+                }
+                "--tables" => {
+                    layout = value()?.parse().map_err(ArgError::InvalidArgument)?;
+                }
+                "--mark" => {
+                    let rule = value()?;
+                    let (mark, meaning) = rule
+                        .split_once('=')
+                        .ok_or_else(|| ArgError::InvalidArgument(rule.clone()))?;
+
+                    let mark: Mark = mark.parse().map_err(ArgError::InvalidArgument)?;
+                    let meaning: MarkMeaning =
+                        meaning.parse().map_err(ArgError::InvalidArgument)?;
+                    annotations.set(mark, meaning);
+                }
+                "--revisions" => {
+                    revisions = value()?.parse().map_err(ArgError::InvalidArgument)?;
+                }
+                "--strict" => strict = true,
+                "--lenient" => strict = false,
+                "--chapter" => chapter = value()?,
+                "--section" => section = value()?,
+                "--undated-section" => undated_section = value()?,
+                "--longtable" => latex.longtable = true,
+                "--booktabs" => latex.booktabs = true,
+                "--provenance" => latex.provenance = true,
+                "--comments" => {
+                    latex.comments = Some(value()?.parse().map_err(ArgError::InvalidArgument)?);
+                }
+                "--footnotes" => {
+                    latex.footnotes = value()?.parse().map_err(ArgError::InvalidArgument)?;
+                }
+                "--column-widths" => {
+                    let widths = value()?;
+                    match widths.split_once(',') {
+                        Some((label, value)) => {
+                            latex.label_width = label.trim().to_owned();
+                            latex.value_width = value.trim().to_owned();
+                        }
+                        None => return Err(ArgError::InvalidArgument(widths)),
+                    }
+                }
+                _ if arg.starts_with('-') => return Err(ArgError::InvalidArgument(arg)),
+                _ => inputs.push(PathBuf::from(arg)),
+            }
+        }
+
+        if inputs.is_empty() {
+            return Err(ArgError::NoArgument);
+        }
+
+        // A single document keeps its own order unless asked otherwise, a
+        // merged catalog is only meaningful when sorted.
+        let sort = sort.unwrap_or(if inputs.len() == 1 && inputs[0].is_file() {
+            SortKey::None
+        } else {
+            SortKey::Row
+        });
+
+        Ok(Self {
+            inputs,
+            sort,
+            schema,
+            boundary,
+            layout,
+            annotations,
+            revisions,
+            strict,
+            output,
+            chapter,
+            section,
+            undated_section,
+            latex,
+        })
+    }
+
+    /// Heading of the section holding the rows of `year`. `{year}` in the
+    /// section template is replaced by the year (or range of years).
+    pub fn section_heading(&self, year: Option<&Range>) -> String {
+        match year {
+            Some(year) => self.section.replace("{year}", &year.to_string()),
+            None => self.undated_section.clone(),
+        }
+    }
+
+    /// Loads the schema given with `--schema`, or the default one.
+    pub fn load_schema(&self) -> Result<Rc<Schema>, SchemaError> {
+        let schema = match &self.schema {
+            Some(path) => Schema::load(path)?,
+            None => Schema::default(),
+        };
+
+        Ok(Rc::new(schema))
+    }
+
+    /// The renderer writing the selected output format.
+    pub fn renderer(&self, schema: &Rc<Schema>) -> Box<dyn Renderer> {
+        match self.output {
+            OutputFormat::Latex => Box::new(LatexRenderer::new(self.latex.clone())),
+            OutputFormat::Csv => Box::new(DelimitedRenderer::new(schema.clone(), b',')),
+            OutputFormat::Tsv => Box::new(DelimitedRenderer::new(schema.clone(), b'\t')),
+            OutputFormat::Json => Box::new(JsonRenderer::new()),
+            OutputFormat::Review => Box::new(ReviewRenderer::new()),
+        }
+    }
+
+    /// Expands the inputs into the list of documents to read. Directories
+    /// contribute every document and spreadsheet they hold directly, in file
+    /// name order.
+    pub fn input_files(&self) -> Result<Vec<PathBuf>, IoError> {
+        let mut files = Vec::new();
+
+        for input in self.inputs.iter() {
+            if input.is_dir() {
+                let mut entries = Vec::new();
+
+                for entry in fs::read_dir(input)? {
+                    let path = entry?.path();
+                    if path.is_file() && is_document(&path) {
+                        entries.push(path);
+                    }
+                }
+
+                entries.sort();
+                files.extend(entries);
+            } else {
+                files.push(input.clone());
+            }
+        }
+
+        Ok(files)
+    }
+}
+
+fn is_document(path: &Path) -> bool {
+    let is_lock_file = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .is_some_and(|n| n.starts_with("~$"));
+
+    is_supported(path) && !is_lock_file
+}
+
+#[test]
+fn sort_by_year() {
+    let row = |key: &str, value: &str| {
+        let mut row = Row::default();
+        row.set_field((key.to_owned(), value.to_owned())).unwrap();
+        row
+    };
+    let year = SortKey::Field("year".to_owned());
+
+    let by_ata = row(
+        "Número e data da Ata de sessão",
+        "5ª sessão de 17 de agosto de 1902",
+    );
+    assert!(by_ata.year().is_none() && by_ata.ata().is_some());
+    assert_eq!(
+        year.compare(&by_ata, &row("Ano da Revista", "1901")),
+        Ordering::Greater
+    );
+    assert_eq!(
+        year.compare(&by_ata, &row("Ano da Revista", "1903")),
+        Ordering::Less
+    );
+}
@@ -1,4 +1,9 @@
-use std::{fmt::Display, mem::swap};
+use std::{
+    fmt::Display,
+    mem::{swap, take},
+};
+
+use crate::diagnostic::Location;
 
 #[derive(Debug)]
 pub enum ParseStatus {
@@ -9,30 +14,91 @@ pub enum ParseStatus {
     SearchingCol2(String),
     Col2(String, String),
     Field(String, String),
+    /// Between the cells of a row read as a list of cells (a row of a grid,
+    /// or a row before the layout of the table is known).
+    Cells(Vec<String>),
+    /// Inside a cell of a row read as a list of cells.
+    Cell(Vec<String>, String),
+    /// Inside a cell spanning the whole row.
+    Span(String),
+    /// After a cell spanning the whole row.
+    Spanned(String),
     Finished,
 }
 
+/// What a table row was made of.
+#[derive(Debug, PartialEq, Eq)]
+pub enum TableRow {
+    /// A key cell and its value cell, `None` when the row had no value cell.
+    Field(String, Option<String>),
+    /// A single cell spanning the whole row (a heading, or text continuing
+    /// the value above it).
+    Span(String),
+    /// The cells of a row read as a list.
+    Cells(Vec<String>),
+}
+
+impl Display for ParseStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseStatus::SearchingTable => write!(f, "outside any table"),
+            ParseStatus::SearchingField => write!(f, "between two rows"),
+            ParseStatus::SearchingCol1 => write!(f, "before the key cell"),
+            ParseStatus::Col1(key) => write!(f, "inside the key cell {:?}", key.trim()),
+            ParseStatus::SearchingCol2(key) => write!(f, "after the key cell {:?}", key.trim()),
+            ParseStatus::Col2(key, _) => write!(f, "inside the value cell of {:?}", key.trim()),
+            ParseStatus::Field(key, _) => write!(f, "after the value cell of {:?}", key.trim()),
+            ParseStatus::Cells(cells) => write!(f, "after cell {} of the row", cells.len()),
+            ParseStatus::Cell(cells, _) => write!(f, "inside cell {} of the row", cells.len() + 1),
+            ParseStatus::Span(text) => write!(f, "inside the row-wide cell {:?}", text.trim()),
+            ParseStatus::Spanned(text) => write!(f, "after the row-wide cell {:?}", text.trim()),
+            ParseStatus::Finished => write!(f, "after the end of the document"),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct ParseError {
     message: String,
+    location: Option<Location>,
+}
+
+impl ParseError {
+    pub(crate) fn new(message: String) -> Self {
+        Self {
+            message,
+            location: None,
+        }
+    }
+
+    /// Attaches the position in the document where the error happened.
+    pub fn at(mut self, location: Location) -> Self {
+        self.location = Some(location);
+        self
+    }
+
+    pub fn location(&self) -> Option<&Location> {
+        self.location.as_ref()
+    }
 }
 
 impl Display for ParseError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "parse error: {}", self.message)
+        match &self.location {
+            Some(location) => write!(f, "{}: {}", location, self.message),
+            None => write!(f, "{}", self.message),
+        }
     }
 }
 
+impl std::error::Error for ParseError {}
+
 impl ParseStatus {
     pub fn start_table(&mut self) -> Result<(), ParseError> {
         // eprintln!("start_table");
         *self = match self {
             ParseStatus::SearchingTable => ParseStatus::SearchingField,
-            _ => {
-                return Err(ParseError {
-                    message: format!("invalid state {:?}", self),
-                })
-            }
+            _ => return Err(ParseError::new(format!("a table starts {}", self))),
         };
         Ok(())
     }
@@ -41,11 +107,16 @@ impl ParseStatus {
         // eprintln!("    start_field");
         *self = match self {
             ParseStatus::SearchingField => ParseStatus::SearchingCol1,
-            _ => {
-                return Err(ParseError {
-                    message: format!("search field on state {:?}", self),
-                })
-            }
+            _ => return Err(ParseError::new(format!("a row starts {}", self))),
+        };
+        Ok(())
+    }
+
+    /// Starts a row read as a list of cells, whatever their number.
+    pub fn start_cells(&mut self) -> Result<(), ParseError> {
+        *self = match self {
+            ParseStatus::SearchingField => ParseStatus::Cells(Vec::new()),
+            _ => return Err(ParseError::new(format!("a row starts {}", self))),
         };
         Ok(())
     }
@@ -60,9 +131,9 @@ impl ParseStatus {
         match self {
             ParseStatus::Col1(key) => push_str(key, text),
             ParseStatus::Col2(_, value) => push_str(value, text),
-            _ => Err(ParseError {
-                message: format!("append text on state {:?}", self),
-            }),
+            ParseStatus::Cell(_, content) => push_str(content, text),
+            ParseStatus::Span(content) => push_str(content, text),
+            _ => Err(ParseError::new(format!("text found {}", self))),
         }
     }
 
@@ -77,9 +148,9 @@ impl ParseStatus {
         match self {
             ParseStatus::Col1(key) => push_space(key),
             ParseStatus::Col2(_, value) => push_space(value),
-            _ => Err(ParseError {
-                message: format!("append text on state {:?}", self),
-            }),
+            ParseStatus::Cell(_, content) => push_space(content),
+            ParseStatus::Span(content) => push_space(content),
+            _ => Err(ParseError::new(format!("text found {}", self))),
         }
     }
 
@@ -92,15 +163,26 @@ impl ParseStatus {
                 swap(&mut aux, key);
                 ParseStatus::Col2(aux, String::new())
             }
+            Self::Cells(cells) => ParseStatus::Cell(take(cells), String::new()),
             _ => {
-                return Err(ParseError {
-                    message: format!("start col on status {:?}", self),
-                })
+                return Err(ParseError::new(format!(
+                    "a cell starts {}, rows must have exactly two cells",
+                    self
+                )))
             }
         };
         Ok(())
     }
 
+    /// Marks the key cell being read as spanning the whole row.
+    pub fn span_col(&mut self) -> Result<(), ParseError> {
+        *self = match self {
+            Self::Col1(text) => Self::Span(take(text)),
+            _ => return Err(ParseError::new(format!("a cell spans the row {}", self))),
+        };
+        Ok(())
+    }
+
     pub fn end_col(&mut self) -> Result<(), ParseError> {
         // eprintln!("        end_col");
         *self = match self {
@@ -116,31 +198,32 @@ impl ParseStatus {
                 swap(&mut aux_value, value);
                 Self::Field(aux_key, aux_value)
             }
-            _ => {
-                return Err(ParseError {
-                    message: format!("end col on status {:?}", self),
-                })
+            Self::Cell(cells, text) => {
+                let mut cells = take(cells);
+                cells.push(take(text));
+                Self::Cells(cells)
             }
+            Self::Span(text) => Self::Spanned(take(text)),
+            _ => return Err(ParseError::new(format!("a cell ends {}", self))),
         };
         Ok(())
     }
 
-    pub fn end_field(&mut self) -> Result<(String, String), ParseError> {
+    /// Ends the row, returning its key and value or the text of the cell
+    /// spanning it.
+    pub fn end_field(&mut self) -> Result<TableRow, ParseError> {
         // eprintln!("    end_field");
         let mut aux = ParseStatus::SearchingField;
         swap(&mut aux, self);
 
         match aux {
-            Self::SearchingCol2(key) => {
-                eprintln!("warning: key: '{}' has no value", key);
-                Ok((key, Default::default()))
-            }
-            Self::Field(key, value) => Ok((key, value)),
+            Self::SearchingCol2(key) => Ok(TableRow::Field(key, None)),
+            Self::Field(key, value) => Ok(TableRow::Field(key, Some(value))),
+            Self::Spanned(text) => Ok(TableRow::Span(text)),
+            Self::Cells(cells) => Ok(TableRow::Cells(cells)),
             aux => {
                 *self = aux;
-                Err(ParseError {
-                    message: format!("end field on status {:?}", self),
-                })
+                Err(ParseError::new(format!("a row ends {}", self)))
             }
         }
     }
@@ -149,11 +232,7 @@ impl ParseStatus {
         // eprintln!("end_table");
         *self = match self {
             ParseStatus::SearchingField => ParseStatus::SearchingTable,
-            _ => {
-                return Err(ParseError {
-                    message: format!("finish table on status {:?}", self),
-                })
-            }
+            _ => return Err(ParseError::new(format!("the table ends {}", self))),
         };
         Ok(())
     }
@@ -171,10 +250,23 @@ impl ParseStatus {
         }
     }
 
+    /// Drops whatever was being read and goes back to looking for a table.
+    pub fn reset(&mut self) {
+        *self = ParseStatus::SearchingTable;
+    }
+
+    /// Whether a cell is being read, which a table may be nested in.
+    pub fn in_cell(&self) -> bool {
+        matches!(
+            self,
+            ParseStatus::Col1(_)
+                | ParseStatus::Col2(..)
+                | ParseStatus::Cell(..)
+                | ParseStatus::Span(_)
+        )
+    }
+
     pub fn is_finished(&self) -> bool {
-        match self {
-            ParseStatus::Finished => true,
-            _ => false,
-        }
+        matches!(self, ParseStatus::Finished)
     }
 }
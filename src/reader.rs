@@ -0,0 +1,2485 @@
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use std::io::{Cursor, Read, Seek};
+use std::mem::{replace, take};
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::str::FromStr;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use xml::attribute::OwnedAttribute;
+use xml::common::Position;
+use xml::reader::{EventReader, XmlEvent};
+use zip::result::ZipError;
+use zip::ZipArchive;
+
+use crate::annotation::AnnotationRules;
+use crate::data::{
+    normalize_key, Comment, FieldState, Note, NoteKind, PlainText, Provenance, RichText, Row,
+    Schema, SetFieldError, Style, SubTable,
+};
+use crate::diagnostic::{ambiguous_key, Location, Warning};
+use crate::format::{attribute, Element, FieldChar, Format};
+use crate::parse::{ParseError, ParseStatus, TableRow};
+use crate::records::Grid;
+use crate::revision::{pending_revisions, Revision, RevisionMode};
+use crate::CallError;
+
+/// What separates two records in the tables of a document.
+///
+/// But for [`Table`](Self::Table) and [`Key`](Self::Key), a key that comes
+/// again in a record ends it with a warning, as a row holds one value per
+/// field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RecordBoundary {
+    /// Every table is one record. A key that comes again adds its value to
+    /// the field, with a warning.
+    Table,
+    /// A record starts at the key of the named field and may go on over
+    /// several tables. Another key that comes again adds its value to the
+    /// field, with a warning.
+    Key(String),
+    /// Records end at a row whose cells are all empty, or at the end of the
+    /// table.
+    BlankRow,
+    /// A record ends when a key already set in it comes again, or at the end
+    /// of the table.
+    DuplicateKey,
+}
+
+impl FromStr for RecordBoundary {
+    type Err = String;
+
+    /// `table`, `key:<field name>`, `blank` or `duplicate`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "table" => Ok(Self::Table),
+            "blank" => Ok(Self::BlankRow),
+            "duplicate" => Ok(Self::DuplicateKey),
+            s => match s.strip_prefix("key:") {
+                Some(name) if !name.is_empty() => Ok(Self::Key(name.replace('-', "_"))),
+                _ => Err(s.to_owned()),
+            },
+        }
+    }
+}
+
+/// How the rows of the document tables are laid out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TableLayout {
+    /// A table whose first row has more than two cells, two of them at
+    /// least naming fields, is a grid, other tables hold keys and values.
+    Auto,
+    /// Every row holds a key and its value.
+    KeyValue,
+    /// The first row holds the headings of the columns, named like the keys,
+    /// and every other row is one record.
+    Grid,
+}
+
+impl FromStr for TableLayout {
+    type Err = String;
+
+    /// `auto`, `key-value` or `grid`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(Self::Auto),
+            "key-value" => Ok(Self::KeyValue),
+            "grid" => Ok(Self::Grid),
+            s => Err(s.to_owned()),
+        }
+    }
+}
+
+/// How documents are read.
+#[derive(Debug, Clone)]
+pub struct ReadOptions {
+    pub schema: Rc<Schema>,
+    pub boundary: RecordBoundary,
+    pub layout: TableLayout,
+    /// How highlighted, shaded and struck text is read.
+    pub annotations: AnnotationRules,
+    /// What is done with the revisions of Word documents.
+    pub revisions: RevisionMode,
+    /// The file being read, recorded in the provenance of the values.
+    pub file: Option<PathBuf>,
+}
+
+impl Default for ReadOptions {
+    /// The default schema, records split at duplicated keys, table layouts
+    /// detected, the default annotation rules, revisions accepted.
+    fn default() -> Self {
+        Self {
+            schema: Rc::new(Schema::default()),
+            boundary: RecordBoundary::DuplicateKey,
+            layout: TableLayout::Auto,
+            annotations: AnnotationRules::default(),
+            revisions: RevisionMode::Accept,
+            file: None,
+        }
+    }
+}
+
+/// Reads the tables of a document body (`word/document.xml` of a docx or
+/// `content.xml` of an odt), producing one `Row` per record.
+pub struct ParseXml<R: Read> {
+    parser: EventReader<R>,
+    format: Format,
+    schema: Rc<Schema>,
+    boundary: RecordBoundary,
+    file: Option<PathBuf>,
+    status: ParseStatus,
+    row: Option<Row>,
+    open_tables: usize,
+    resync: bool,
+    ignored: usize,
+    table: usize,
+    table_row: usize,
+    cell: usize,
+    table_layout: TableLayout,
+    annotations: AnnotationRules,
+    revisions: RevisionMode,
+    /// Whether the current row is left out, as inserted or deleted by a
+    /// revision dropped.
+    dropped_row: bool,
+    /// Revision being read when revisions are refused, which ends the
+    /// reading.
+    refused: Option<Revision>,
+    /// What the rows of the current table are read as.
+    layout: Layout,
+    /// Whether the first cell of the current row spans the whole row.
+    spanning: bool,
+    /// Whether the key cell of the current row continues the one above it.
+    merged: bool,
+    /// Cells of the current row spanning several grid columns, with their
+    /// span (`w:gridSpan`, or a first cell whose covered cells are skipped).
+    cell_spans: Vec<(usize, usize)>,
+    /// Cells of the current row that continue the cell above them.
+    merged_cells: Vec<usize>,
+    /// Covered cells still to come on the row for the last cell spanning
+    /// columns, which are not merged with the row above.
+    covering: usize,
+    /// Values of the last grid record by column, and its formatted texts,
+    /// which merged cells repeat.
+    above: Vec<String>,
+    above_texts: Vec<(usize, RichText, FieldState)>,
+    /// Table being read inside a cell.
+    nested: Option<NestedTable>,
+    /// Tables read inside the cells of the current row, with their cell.
+    cell_tables: Vec<(usize, SubTable)>,
+    /// Key of the last field read in the current table, which rows
+    /// continuing it are added to.
+    last_key: Option<String>,
+    /// Formatting of the current Word run.
+    run_style: Style,
+    /// Formatting of the OpenDocument spans the text is in, innermost last.
+    span_styles: Vec<Style>,
+    /// Formatting of the OpenDocument text styles, by name.
+    text_styles: HashMap<String, Style>,
+    /// Name of the text style being defined.
+    defining_style: Option<String>,
+    /// Formatted text of the current cell.
+    rich: RichText,
+    /// Formatted text of the cells of the current row, with their cell.
+    cell_texts: Vec<(usize, RichText)>,
+    /// Comments of the document not yet anchored, by id.
+    comments: HashMap<String, Comment>,
+    /// Comments anchored in the cells of the current row, with their cell.
+    cell_comments: Vec<(usize, Comment)>,
+    /// Footnotes and endnotes of the document not yet called, by kind and id.
+    notes: HashMap<(NoteKind, String), Note>,
+    /// Notes called in the cells of the current row, with their cell.
+    cell_notes: Vec<(usize, Note)>,
+    /// Text of the current paragraph outside tables, without field codes.
+    paragraph: String,
+    /// Instructions of the fields of the current paragraph.
+    instructions: Vec<String>,
+    /// Text of the `w:instrText` being read.
+    field_code: Option<String>,
+    /// Complex fields begun and not yet ended, innermost last.
+    fields: Vec<ComplexField>,
+    /// Caption waiting for the next table.
+    pending_caption: Option<(String, Option<u32>)>,
+    caption: Option<String>,
+    table_number: Option<u32>,
+    table_numbers: BTreeSet<u32>,
+    last_number: Option<u32>,
+    warnings: VecDeque<Warning>,
+}
+
+impl<R: Read> ParseXml<R> {
+    /// Reads a WordprocessingML document with the default options.
+    pub fn new(r: R) -> Self {
+        Self::with_format(r, Format::Docx)
+    }
+
+    pub fn with_format(r: R, format: Format) -> Self {
+        Self::with_options(r, format, ReadOptions::default())
+    }
+
+    pub fn with_options(r: R, format: Format, options: ReadOptions) -> Self {
+        Self {
+            parser: EventReader::new(r),
+            format,
+            schema: options.schema,
+            boundary: options.boundary,
+            file: options.file,
+            status: ParseStatus::SearchingTable,
+            row: None,
+            open_tables: 0,
+            resync: false,
+            ignored: 0,
+            table: 0,
+            table_row: 0,
+            cell: 0,
+            table_layout: options.layout,
+            annotations: options.annotations,
+            revisions: options.revisions,
+            dropped_row: false,
+            refused: None,
+            layout: Layout::Unknown,
+            spanning: false,
+            merged: false,
+            cell_spans: Vec::new(),
+            merged_cells: Vec::new(),
+            covering: 0,
+            above: Vec::new(),
+            above_texts: Vec::new(),
+            nested: None,
+            cell_tables: Vec::new(),
+            last_key: None,
+            run_style: Style::default(),
+            span_styles: Vec::new(),
+            text_styles: HashMap::new(),
+            defining_style: None,
+            rich: RichText::default(),
+            cell_texts: Vec::new(),
+            comments: HashMap::new(),
+            cell_comments: Vec::new(),
+            notes: HashMap::new(),
+            cell_notes: Vec::new(),
+            paragraph: String::new(),
+            instructions: Vec::new(),
+            field_code: None,
+            fields: Vec::new(),
+            pending_caption: None,
+            caption: None,
+            table_number: None,
+            table_numbers: BTreeSet::new(),
+            last_number: None,
+            warnings: VecDeque::new(),
+        }
+    }
+
+    /// Where the parser currently is in the document.
+    pub fn location(&self) -> Location {
+        Location {
+            table: self.table,
+            caption: self.caption.clone(),
+            row: self.table_row,
+            cell: self.cell,
+            position: Some(self.parser.position()),
+        }
+    }
+
+    /// Takes the oldest warning not yet reported.
+    pub fn take_warning(&mut self) -> Option<Warning> {
+        self.warnings.pop_front()
+    }
+
+    /// The current location, at another cell of the row.
+    fn cell_location(&self, cell: usize) -> Location {
+        Location {
+            cell,
+            ..self.location()
+        }
+    }
+
+    fn warn(&mut self, message: String) {
+        let location = self.location();
+        self.warnings.push_back(Warning { location, message });
+    }
+
+    /// Reads the document up to the end of the next row.
+    ///
+    /// After a structural error the current table is abandoned: the fields
+    /// read so far are kept and the parser resumes at the next table.
+    pub fn next_row(&mut self) -> Result<Row, CallError> {
+        if self.status.is_finished() {
+            return self.row.take().ok_or(CallError::AlreadyFinished);
+        }
+
+        let schema = &self.schema;
+        let mut row = self.row.take().unwrap_or_else(|| Row::new(schema.clone()));
+
+        let result = loop {
+            let event = match self.parser.next() {
+                Ok(event) => event,
+                Err(e) => {
+                    self.status.finish().ok();
+                    break Err(e.into());
+                }
+            };
+
+            match self.step(event, &mut row) {
+                Ok(None) => {}
+                Ok(Some(done)) => break Ok(done),
+                Err(CallError::Parse(e)) => {
+                    let e = e.at(self.location());
+                    self.status.reset();
+                    self.resync = self.open_tables > 0;
+                    break Err(CallError::Parse(e));
+                }
+                Err(e) => break Err(e),
+            }
+        };
+
+        if !row.is_empty() {
+            self.row = Some(row);
+        }
+
+        result
+    }
+
+    fn step(&mut self, event: XmlEvent, row: &mut Row) -> Result<Option<Row>, CallError> {
+        if self.ignored > 0 {
+            match event {
+                XmlEvent::StartElement { .. } => self.ignored += 1,
+                XmlEvent::EndElement { .. } => self.ignored -= 1,
+                XmlEvent::Characters(text) => {
+                    if let Some(revision) = self.refused.as_mut() {
+                        revision.text.push_str(&text);
+                    }
+                }
+                _ => {}
+            }
+
+            if self.ignored == 0 {
+                if let Some(mut revision) = self.refused.take() {
+                    revision.text = revision.text.trim().to_owned();
+                    *row = Row::new(self.schema.clone());
+                    self.status.finish().ok();
+
+                    return Err(CallError::Revisions(vec![revision]));
+                }
+            }
+
+            return Ok(None);
+        }
+
+        if self.step_nested(&event) {
+            return Ok(None);
+        }
+
+        match event {
+            XmlEvent::StartElement {
+                name, attributes, ..
+            } => match self.format.element(&name, &attributes) {
+                Element::Ignored => self.ignored = 1,
+                Element::Comment(id) => self.anchor_comment(&id),
+                Element::NoteReference(kind, id) => self.anchor_note(kind, id),
+                Element::Revision(kind, author, date) if self.revisions == RevisionMode::Fail => {
+                    self.refused = Some(Revision {
+                        kind,
+                        author,
+                        date,
+                        text: String::new(),
+                    });
+                    self.ignored = 1;
+                }
+                Element::TrackedChanges => {
+                    self.warn(
+                        "tracked changes are not supported in OpenDocument, \
+                         the text is read as if they were all accepted"
+                            .to_owned(),
+                    );
+                    self.ignored = 1;
+                }
+                Element::Revision(kind, ..) if self.revisions.drops(kind) => {
+                    // A revision before the first cell of a row is about the
+                    // row itself.
+                    self.dropped_row |= self.open_tables > 0 && self.cell == 0;
+                    self.ignored = 1;
+                }
+                Element::Run => self.run_style = Style::default(),
+                Element::Emphasis(emphasis, on) => emphasis.apply(&mut self.run_style, on),
+                Element::TextStyle(name) => self.defining_style = name,
+                Element::TextProperties(style) => {
+                    if let Some(name) = self.defining_style.clone() {
+                        self.text_styles.insert(name, style);
+                    }
+                }
+                Element::StyledSpan(name) => {
+                    let style = name
+                        .and_then(|name| self.text_styles.get(&name).copied())
+                        .unwrap_or_default();
+                    self.span_styles.push(self.style().with(style));
+                }
+                Element::Table => {
+                    self.open_tables += 1;
+                    if self.open_tables == 1 {
+                        self.table += 1;
+                        self.table_row = 0;
+                        self.cell = 0;
+                        self.last_key = None;
+                        self.above.clear();
+                        self.above_texts.clear();
+                        self.layout = match self.table_layout {
+                            TableLayout::KeyValue => Layout::KeyValue,
+                            _ => Layout::Unknown,
+                        };
+                        let (caption, number) = self.pending_caption.take().unzip();
+                        self.caption = caption;
+                        self.table_number = number.flatten();
+                    }
+                    if !self.resync {
+                        if self.open_tables > 1 && self.status.in_cell() {
+                            self.nested = Some(NestedTable::new(self.open_tables));
+                        } else {
+                            self.status.start_table()?;
+                        }
+                    }
+                }
+                _ if self.resync => {}
+                Element::Row => {
+                    self.table_row += 1;
+                    self.cell = 0;
+                    self.merged = false;
+                    self.spanning = false;
+                    self.cell_spans.clear();
+                    self.merged_cells.clear();
+                    self.covering = 0;
+                    self.cell_tables.clear();
+                    self.cell_texts.clear();
+                    self.cell_comments.clear();
+                    self.cell_notes.clear();
+                    self.dropped_row = false;
+
+                    match self.layout {
+                        Layout::KeyValue => self.status.start_field()?,
+                        _ => self.status.start_cells()?,
+                    }
+                }
+                // The cells hidden by a cell spanning the row are skipped.
+                Element::CoveredCell if self.spanning => self.ignored = 1,
+                Element::Cell(columns) => {
+                    self.cell += 1;
+                    self.rich = RichText::default();
+                    self.status.start_col()?;
+                    // The cells it covers come next, but for the first cell.
+                    self.covering = match self.cell {
+                        1 => 0,
+                        _ => columns.saturating_sub(1),
+                    };
+                    self.span_cell(columns)?
+                }
+                Element::CoveredCell => {
+                    self.cell += 1;
+                    self.rich = RichText::default();
+                    self.merged |= self.cell == 1;
+
+                    // A cell not covered by one before it on the row is
+                    // covered by the cell above it.
+                    match self.covering {
+                        0 => self.merged_cells.push(self.cell),
+                        _ => self.covering -= 1,
+                    }
+
+                    self.status.start_col()?
+                }
+                Element::GridSpan(columns) => {
+                    if self.cell > 1 && columns > 1 {
+                        self.cell_spans.push((self.cell, columns));
+                    }
+
+                    self.span_cell(columns)?
+                }
+                Element::MergedCell => {
+                    self.merged |= self.cell == 1;
+                    self.merged_cells.push(self.cell);
+                }
+                Element::Paragraph => {
+                    if self.status.append_new_line().is_ok() && !self.rich.is_empty() {
+                        self.rich.push(Style::default(), " ");
+                    }
+                }
+                Element::FieldCode => self.field_code = Some(String::new()),
+                Element::FieldChar(FieldChar::Begin) => self.fields.push(ComplexField::default()),
+                Element::FieldChar(FieldChar::Separate) => {
+                    if let Some(field) = self.fields.last_mut() {
+                        field.result = true;
+                    }
+                }
+                Element::FieldChar(FieldChar::End) => {
+                    if let Some(field) = self.fields.pop() {
+                        self.end_field(field.instruction);
+                    }
+                }
+                Element::SimpleField(instruction) => self.end_field(instruction),
+                Element::Text(true) => self.append_text(" "),
+                Element::Space(count) => self.append_text(&" ".repeat(count)),
+                Element::Text(false) | Element::Revision(..) | Element::Other => {}
+            },
+            XmlEvent::EndElement { name }
+                if self.format.element(&name, &[]) == Element::FieldCode =>
+            {
+                if let Some(instruction) = self.field_code.take() {
+                    match self.fields.last_mut().filter(|field| !field.result) {
+                        Some(field) => field.instruction.push_str(&instruction),
+                        None => self.end_field(instruction),
+                    }
+                }
+            }
+            XmlEvent::EndElement { name } => match self.format.element(&name, &[]) {
+                Element::Table => {
+                    self.open_tables = self.open_tables.saturating_sub(1);
+
+                    // With a record key, records go on in the next table.
+                    let ends_record = !matches!(self.boundary, RecordBoundary::Key(_));
+
+                    if self.resync {
+                        if self.open_tables == 0 {
+                            self.resync = false;
+                            if ends_record && !row.is_empty() {
+                                return Ok(Some(self.take_row(row)));
+                            }
+                        }
+                    } else {
+                        self.status.end_table()?;
+                        if ends_record {
+                            return Ok(Some(self.take_row(row)).filter(|row| !row.is_empty()));
+                        }
+                    }
+                }
+                Element::Paragraph if self.open_tables == 0 => self.end_paragraph()?,
+                Element::TextStyle(_) => self.defining_style = None,
+                Element::StyledSpan(_) => {
+                    self.span_styles.pop();
+                }
+                _ if self.resync => {}
+                Element::Cell(_) | Element::CoveredCell => {
+                    self.status.end_col()?;
+                    self.cell_texts.push((self.cell, take(&mut self.rich)));
+                }
+                Element::Row if self.dropped_row => {
+                    self.status.end_field()?;
+                    self.drop_comments();
+                    self.drop_notes();
+                }
+                Element::Row => {
+                    let result = match self.status.end_field()? {
+                        TableRow::Field(key, value) => self.end_pair(row, key, value),
+                        TableRow::Span(text) => self.end_span(row, text),
+                        TableRow::Cells(cells) => self.end_cells(row, cells),
+                    };
+
+                    self.drop_comments();
+                    self.drop_notes();
+                    return result;
+                }
+                _ => {}
+            },
+            // Text between the begin and separate marks of a field that is
+            // not in a `w:instrText` is dropped as well.
+            XmlEvent::Characters(_) if self.field_code.is_none() && self.in_instruction() => {}
+            XmlEvent::Characters(text) => match self.field_code.as_mut() {
+                Some(instruction) => instruction.push_str(&text),
+                None if self.open_tables == 0 => self.paragraph.push_str(&text),
+                None if !self.resync => {
+                    self.status.append_text(&text)?;
+                    self.rich.push(self.style(), &text);
+                }
+                None => {}
+            },
+            XmlEvent::EndDocument => {
+                return match self.status.finish() {
+                    Ok(_) if row.is_empty() => Err(CallError::AlreadyFinished),
+                    Ok(_) => Ok(Some(self.take_row(row))),
+                    Err(f) => {
+                        let provenance = self.provenance(&f.1);
+
+                        Ok(Some(
+                            self.set_field(row, f, provenance)
+                                .unwrap_or_else(|| self.take_row(row)),
+                        ))
+                    }
+                };
+            }
+            _ => {}
+        }
+
+        Ok(None)
+    }
+
+    /// Ends a row holding a key and its value.
+    fn end_pair(
+        &mut self,
+        row: &mut Row,
+        key: String,
+        value: Option<String>,
+    ) -> Result<Option<Row>, CallError> {
+        // A merged key cell repeats the key above it, the value is added to
+        // that field.
+        if let Some(key) = self.last_key.clone().filter(|_| self.merged) {
+            self.add_to_field(row, &key);
+            return Ok(None);
+        }
+
+        // A record that only ends at a table or at its first key is not
+        // split at another repeated key.
+        if row.has(&key) && self.adds_repeated(&key) {
+            self.warn(repeated_in_record(&key));
+            self.add_to_field(row, &key);
+            self.last_key = Some(key);
+            return Ok(None);
+        }
+
+        let is_blank = key.trim().is_empty() && value.as_deref().unwrap_or("").trim().is_empty();
+
+        // A value without key names no field, not even an extra one.
+        if !is_blank && normalize_key(&key).is_empty() {
+            if let Some(value) = value.as_deref().filter(|v| !v.trim().is_empty()) {
+                self.warn(format!(
+                    "value {:?} has no key, it was left out",
+                    value.trim()
+                ));
+                self.last_key = None;
+                return Ok(None);
+            }
+        }
+
+        self.last_key = Some(key.clone()).filter(|_| !is_blank);
+
+        if is_blank && self.boundary == RecordBoundary::BlankRow {
+            return Ok(Some(self.take_row(row)).filter(|row| !row.is_empty()));
+        }
+
+        let value = value.unwrap_or_else(|| {
+            self.warn(format!("key {:?} has no value", key.trim()));
+            Default::default()
+        });
+
+        let provenance = self.provenance(&value);
+        let (text, state) = self.annotated_text(2);
+
+        // Removed text is left out of the value, its provenance keeps it.
+        let value = match state.removed {
+            Some(_) => PlainText(&text).to_string(),
+            None => value,
+        };
+
+        let finished = self.set_field(row, (key.clone(), value), provenance);
+
+        row.set_rich_text(&key, text);
+        row.annotate(&key, state);
+        self.add_sub_tables(row, &key);
+        self.add_comments(row, &key);
+        self.add_notes(row, &key);
+
+        Ok(finished)
+    }
+
+    /// Adds the text of the value cell of the current row to the values of
+    /// `key`, with its marks, tables, comments and notes.
+    fn add_to_field(&mut self, row: &mut Row, key: &str) {
+        let (text, state) = self.annotated_text(2);
+        row.add_value(key, &text);
+        row.annotate(key, state);
+        self.add_sub_tables(row, key);
+        self.add_comments(row, key);
+        self.add_notes(row, key);
+    }
+
+    /// Attaches the tables read inside the value cell of the current row to
+    /// the field of `key`. A table in the key cell is reported and dropped.
+    fn add_sub_tables(&mut self, row: &mut Row, key: &str) {
+        for (cell, table) in take(&mut self.cell_tables) {
+            if cell == 1 && !self.spanning {
+                self.warn(format!(
+                    "the key cell of {:?} holds a table, it was ignored",
+                    key.trim()
+                ));
+            } else {
+                row.add_sub_table(key, table);
+            }
+        }
+    }
+
+    /// Anchors a comment in the cell being read. Comments outside tables
+    /// are reported and dropped.
+    fn anchor_comment(&mut self, id: &str) {
+        let comment = match self.comments.remove(id) {
+            Some(comment) => comment,
+            None => return,
+        };
+
+        if self.open_tables > 0 && self.status.in_cell() {
+            self.cell_comments.push((self.cell, comment));
+        } else {
+            self.warn(format!(
+                "comment {:?} is not in a table cell, it was ignored",
+                comment.text
+            ));
+        }
+    }
+
+    /// Anchors a footnote or endnote in the cell calling it. Notes called
+    /// outside tables are reported and dropped.
+    fn anchor_note(&mut self, kind: NoteKind, id: String) {
+        let note = match self.notes.remove(&(kind, id)) {
+            Some(note) => note,
+            None => return,
+        };
+
+        if self.open_tables > 0 && self.status.in_cell() {
+            self.cell_notes.push((self.cell, note));
+        } else {
+            self.warn(format!(
+                "note {:?} is not called in a table cell, it was ignored",
+                note.text
+            ));
+        }
+    }
+
+    /// Attaches the comments anchored in the current row to the field of
+    /// `key`, whichever cell they are in.
+    fn add_comments(&mut self, row: &mut Row, key: &str) {
+        for (_, comment) in take(&mut self.cell_comments) {
+            row.add_comment(key, comment);
+        }
+    }
+
+    /// Attaches the notes called in the current row to the field of `key`,
+    /// whichever cell calls them.
+    fn add_notes(&mut self, row: &mut Row, key: &str) {
+        for (_, note) in take(&mut self.cell_notes) {
+            row.add_note(key, note);
+        }
+    }
+
+    /// Reports the notes called in the current row that no field took.
+    fn drop_notes(&mut self) {
+        for (cell, note) in take(&mut self.cell_notes) {
+            let location = self.cell_location(cell);
+            let message = format!(
+                "note {:?} is called from a cell that holds no field, it was ignored",
+                note.text
+            );
+
+            self.warnings.push_back(Warning { location, message });
+        }
+    }
+
+    /// Reports the comments of the current row that no field took, as they
+    /// are on a heading or on a cell without heading.
+    fn drop_comments(&mut self) {
+        for (cell, comment) in take(&mut self.cell_comments) {
+            let location = self.cell_location(cell);
+            let message = format!(
+                "comment {:?} is on a cell that holds no field, it was ignored",
+                comment.text
+            );
+
+            self.warnings.push_back(Warning { location, message });
+        }
+    }
+
+    /// Reads the elements of a table nested in a cell as lines of cells,
+    /// tables nested deeper being read as the text of their cell. Returns
+    /// `false` for the events left to [`step`](Self::step): field codes and
+    /// content that is not part of the text flow.
+    fn step_nested(&mut self, event: &XmlEvent) -> bool {
+        let in_instruction = self.field_code.is_some() || self.in_instruction();
+
+        let nested = match self.nested.as_mut() {
+            Some(nested) => nested,
+            None => return false,
+        };
+        let at_level = self.open_tables == nested.level;
+
+        match event {
+            XmlEvent::StartElement {
+                name, attributes, ..
+            } => match self.format.element(name, attributes) {
+                Element::Table => self.open_tables += 1,
+                Element::Row if at_level => nested.lines.push(Vec::new()),
+                Element::Row | Element::Cell(_) | Element::CoveredCell | Element::Paragraph => {
+                    nested.new_line()
+                }
+                Element::Text(true) if !in_instruction => nested.cell.push(' '),
+                Element::Space(count) if !in_instruction => {
+                    nested.cell.push_str(&" ".repeat(count))
+                }
+                Element::Text(_) | Element::Space(_) => {}
+                _ => return false,
+            },
+            XmlEvent::EndElement { name } => match self.format.element(name, &[]) {
+                Element::Table => {
+                    self.open_tables -= 1;
+
+                    if at_level {
+                        let lines = take(&mut nested.lines);
+                        self.nested = None;
+                        self.cell_tables.push((self.cell, SubTable::new(lines)));
+                    }
+                }
+                Element::Cell(_) | Element::CoveredCell if at_level => nested.end_cell(),
+                Element::Row | Element::Cell(_) | Element::CoveredCell => {}
+                _ => return false,
+            },
+            XmlEvent::Characters(text) if !in_instruction => nested.cell.push_str(text),
+            _ => return false,
+        }
+
+        true
+    }
+
+    /// Ends a row read as a list of cells. Before the layout of the table is
+    /// known, a row that [`Grid::is_heading`] tells is the heading line of a
+    /// grid, any other row makes the table a key/value one. In a grid, every
+    /// row is one record, its cells put in the columns they span, and a
+    /// merged cell repeats the value of the record above.
+    fn end_cells(
+        &mut self,
+        row: &mut Row,
+        mut cells: Vec<String>,
+    ) -> Result<Option<Row>, CallError> {
+        if self.spanning && cells.len() == 1 {
+            return self.end_span(row, cells.remove(0));
+        }
+
+        if cells.iter().all(|cell| cell.trim().is_empty()) {
+            return Ok(None);
+        }
+
+        let mut warnings = Vec::new();
+
+        let result = match &self.layout {
+            // Reported once, at the heading line.
+            Layout::Grid(grid) if !grid.names_fields() => None,
+            Layout::Grid(grid) => {
+                let mut cells = self.align(cells);
+                let mut texts: Vec<_> = self
+                    .cell_texts
+                    .iter()
+                    .map(|(cell, text)| {
+                        let (text, state) = self.annotations.apply(text);
+                        (self.column(*cell), text, state)
+                    })
+                    .collect();
+
+                for (col, text, state) in &texts {
+                    if let Some(value) = cells.get_mut(*col).filter(|_| state.removed.is_some()) {
+                        *value = PlainText(text).to_string();
+                    }
+                }
+
+                // A merged cell repeats the value of the record above.
+                for col in self.merged_cells.iter().map(|cell| self.column(*cell)) {
+                    let value = match cells.get_mut(col) {
+                        Some(value) if value.trim().is_empty() => value,
+                        _ => continue,
+                    };
+
+                    if let Some(above) = self.above.get(col) {
+                        value.clone_from(above);
+                    }
+
+                    texts.retain(|(c, ..)| *c != col);
+                    texts.extend(self.above_texts.iter().filter(|(c, ..)| *c == col).cloned());
+                }
+
+                self.above = cells.clone();
+                self.above_texts = texts.clone();
+
+                let mut record = grid.record(
+                    self.file.as_ref(),
+                    cells,
+                    |col| self.cell_location(col + 1),
+                    &mut warnings,
+                );
+
+                for (col, text, state) in texts {
+                    if let Some(heading) = grid.heading(col) {
+                        record.set_rich_text(heading, text);
+                        record.annotate(heading, state);
+                    }
+                }
+
+                for (cell, table) in take(&mut self.cell_tables) {
+                    if let Some(heading) = grid.heading(self.column(cell)) {
+                        record.add_sub_table(heading, table);
+                    }
+                }
+
+                for (cell, comment) in take(&mut self.cell_comments) {
+                    match grid.heading(self.column(cell)) {
+                        Some(heading) => record.add_comment(heading, comment),
+                        None => self.cell_comments.push((cell, comment)),
+                    }
+                }
+
+                for (cell, note) in take(&mut self.cell_notes) {
+                    match grid.heading(self.column(cell)) {
+                        Some(heading) => record.add_note(heading, note),
+                        None => self.cell_notes.push((cell, note)),
+                    }
+                }
+
+                record.set_table_number(self.table_number);
+                Some(record)
+            }
+            _ if self.table_layout == TableLayout::Grid
+                || Grid::is_heading(&self.schema, &cells) =>
+            {
+                let grid = Grid::new(
+                    self.schema.clone(),
+                    self.align(cells),
+                    |col| self.cell_location(col + 1),
+                    &mut warnings,
+                );
+
+                self.layout = Layout::Grid(grid);
+
+                // A record left open by the previous tables ends here.
+                Some(self.take_row(row)).filter(|row| !row.is_empty())
+            }
+            _ => {
+                self.layout = Layout::KeyValue;
+
+                if cells.len() > 2 {
+                    return Err(ParseError::new(format!(
+                        "a row has {} cells, rows must have exactly two cells",
+                        cells.len()
+                    ))
+                    .into());
+                }
+
+                let mut cells = cells.into_iter();
+                let key = cells.next().unwrap_or_default();
+
+                return self.end_pair(row, key, cells.next());
+            }
+        };
+
+        self.warnings.extend(warnings);
+
+        Ok(result)
+    }
+
+    /// Grid column of a cell of the current row, past the columns spanned by
+    /// the cells before it.
+    fn column(&self, cell: usize) -> usize {
+        let spanned: usize = self
+            .cell_spans
+            .iter()
+            .filter(|(c, _)| *c < cell)
+            .map(|(_, columns)| columns - 1)
+            .sum();
+
+        cell - 1 + spanned
+    }
+
+    /// Puts the cells of the current row in their grid columns, the columns
+    /// spanned by a cell being left empty.
+    fn align(&self, cells: Vec<String>) -> Vec<String> {
+        let mut aligned = Vec::with_capacity(cells.len());
+
+        for (i, cell) in cells.into_iter().enumerate() {
+            aligned.resize(self.column(i + 1), String::new());
+            aligned.push(cell);
+        }
+
+        aligned
+    }
+
+    /// Makes the key cell being read span the whole row when it spans
+    /// several grid columns.
+    fn span_cell(&mut self, columns: usize) -> Result<(), CallError> {
+        if self.cell == 1 && columns > 1 {
+            self.spanning = true;
+            self.cell_spans.push((1, columns));
+
+            if let Layout::KeyValue = self.layout {
+                self.status.span_col()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Ends a row made of a single cell. It continues the value of the row
+    /// above it, or it is a heading when no field was read before it in the
+    /// table. Headings are given to the caller as paragraphs.
+    fn end_span(&mut self, row: &mut Row, text: String) -> Result<Option<Row>, CallError> {
+        if let Some(key) = self.last_key.clone() {
+            let (text, state) = self.annotated_text(1);
+            row.extend_field(&key, " ", &text);
+            row.annotate(&key, state);
+            self.add_sub_tables(row, &key);
+            self.add_comments(row, &key);
+            self.add_notes(row, &key);
+            return Ok(None);
+        }
+
+        let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+
+        if text.is_empty() {
+            return Ok(None);
+        }
+
+        Err(CallError::Paragraph(text))
+    }
+
+    /// Appends text typed as elements (spaces, tabs) to the cell or paragraph
+    /// being read.
+    fn append_text(&mut self, text: &str) {
+        if self.in_instruction() {
+            return;
+        }
+
+        if self.open_tables == 0 {
+            self.paragraph.push_str(text);
+        } else if !self.resync && self.status.append_text(text).is_ok() {
+            self.rich.push(self.style(), text);
+        }
+    }
+
+    /// Formatting of the text being read.
+    fn style(&self) -> Style {
+        self.span_styles.last().copied().unwrap_or(self.run_style)
+    }
+
+    /// Formatted text of a cell of the current row once the annotation rules
+    /// are applied, with the state they give to its value.
+    fn annotated_text(&self, cell: usize) -> (RichText, FieldState) {
+        self.annotations.apply(&self.cell_text(cell))
+    }
+
+    /// Formatted text of a cell of the current row, empty when the row has
+    /// no such cell.
+    fn cell_text(&self, cell: usize) -> RichText {
+        self.cell_texts
+            .iter()
+            .find(|(c, _)| *c == cell)
+            .map(|(_, text)| text.clone())
+            .unwrap_or_default()
+    }
+
+    /// Whether the text read belongs to the instruction of a complex field,
+    /// which is never part of the document text.
+    fn in_instruction(&self) -> bool {
+        self.fields.last().is_some_and(|field| !field.result)
+    }
+
+    /// Keeps the instruction of a field whose result was read. Only the
+    /// fields of paragraphs outside tables are looked at, to find captions.
+    fn end_field(&mut self, instruction: String) {
+        if self.open_tables == 0 {
+            self.instructions.push(instruction);
+        }
+    }
+
+    /// Ends a paragraph outside tables. Captions ("Tabela 57" with a `SEQ`
+    /// field) are kept for the next table, other paragraphs are given to the
+    /// caller as `CallError::Paragraph`.
+    fn end_paragraph(&mut self) -> Result<(), CallError> {
+        let text = take(&mut self.paragraph)
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        let instructions = take(&mut self.instructions);
+
+        let has_field = |name: &str| {
+            instructions
+                .iter()
+                .any(|i| field_name(i).as_deref() == Some(name))
+        };
+
+        // A cross-reference to a caption ("REF _Ref123 \h") has the same
+        // text as the caption but is not one.
+        let is_caption = has_field("SEQ") || (CAPTION.is_match(&text) && !has_field("REF"));
+
+        if is_caption {
+            // The number of the table, not a year typed after it.
+            let number = TABLE_NUMBER
+                .captures(&text)
+                .and_then(|c| c.get(1))
+                .or_else(|| NUMBER.find(&text))
+                .and_then(|n| n.as_str().parse().ok());
+
+            self.check_number(&text, number);
+            self.pending_caption = Some((text, number));
+
+            return Ok(());
+        }
+
+        if text.is_empty() {
+            return Ok(());
+        }
+
+        // A caption names the table right after it: one that is followed by
+        // text (a caption under its table) names no table.
+        self.pending_caption = None;
+
+        Err(CallError::Paragraph(text))
+    }
+
+    /// Reports caption numbers used twice or skipped. The warnings are
+    /// located after the last table read, at the caption.
+    fn check_number(&mut self, caption: &str, number: Option<u32>) {
+        let message = match number {
+            None => Some(format!("caption {:?} has no number", caption)),
+            Some(number) if !self.table_numbers.insert(number) => Some(format!(
+                "caption {:?} repeats table number {}",
+                caption, number
+            )),
+            Some(number) => match self.last_number {
+                Some(last) if Some(number) == last.checked_add(2) => Some(format!(
+                    "caption {:?} skips table number {}",
+                    caption,
+                    last + 1
+                )),
+                Some(last) if number > last.saturating_add(2) => Some(format!(
+                    "caption {:?} skips table numbers {} to {}",
+                    caption,
+                    last + 1,
+                    number - 1
+                )),
+                _ => None,
+            },
+        };
+
+        if let Some(message) = message {
+            let location = Location {
+                caption: None,
+                row: 0,
+                cell: 0,
+                ..self.location()
+            };
+            self.warnings.push_back(Warning { location, message });
+        }
+
+        if number.is_some() {
+            self.last_number = number;
+        }
+    }
+
+    /// Provenance of a value read in the current row.
+    fn provenance(&self, raw: &str) -> Provenance {
+        Provenance {
+            file: self.file.clone(),
+            table: self.table,
+            row: self.table_row,
+            caption: self.caption.clone(),
+            raw: raw.to_owned(),
+        }
+    }
+
+    /// Takes the finished record out of `row`, leaving an empty one.
+    /// Whether a key the record already has adds its value to it rather
+    /// than starting a new record.
+    fn adds_repeated(&self, key: &str) -> bool {
+        match &self.boundary {
+            RecordBoundary::Table => true,
+            RecordBoundary::Key(name) => self.schema.classify(key) != self.schema.position(name),
+            RecordBoundary::BlankRow | RecordBoundary::DuplicateKey => false,
+        }
+    }
+
+    fn take_row(&self, row: &mut Row) -> Row {
+        replace(row, Row::new(self.schema.clone()))
+    }
+
+    /// Stores a field in `row`. When the field starts a record (see
+    /// [`RecordBoundary::Key`]) or the row already has it, the record is
+    /// over: the finished row is returned and `row` is replaced by a new one
+    /// starting with the field. With [`RecordBoundary::Table`], and with
+    /// [`RecordBoundary::Key`] but for its key, a field the row already has
+    /// is added to instead.
+    fn set_field(
+        &mut self,
+        row: &mut Row,
+        field: (String, String),
+        provenance: Provenance,
+    ) -> Option<Row> {
+        if let Some(message) = ambiguous_key(&self.schema, &field.0) {
+            self.warn(message);
+        }
+
+        // A record spread over several tables keeps the number of the first.
+        if row.is_empty() || row.table_number().is_none() {
+            row.set_table_number(self.table_number);
+        }
+
+        if self.schema.classify(&field.0).is_none() && !field.0.trim().is_empty() {
+            self.warn(format!(
+                "key {:?} names no field, kept as an extra field",
+                field.0.trim()
+            ));
+        }
+
+        if let RecordBoundary::Key(name) = &self.boundary {
+            let starts_record = match (self.schema.classify(&field.0), self.schema.position(name)) {
+                (Some(field), Some(first)) => field == first,
+                _ => false,
+            };
+
+            if starts_record && !row.is_empty() {
+                let finished = self.take_row(row);
+                self.store(row, field, provenance).ok();
+                row.set_table_number(self.table_number);
+
+                return Some(finished);
+            }
+        }
+
+        match self.store(row, field, provenance.clone()) {
+            Ok(()) => None,
+            Err((key, value)) if self.adds_repeated(&key) => {
+                self.warn(repeated_in_record(&key));
+                row.add_value(&key, &RichText::plain(&value));
+
+                None
+            }
+            Err((key, value)) => {
+                self.warn(format!("key {:?} is repeated, it starts a new record", key));
+
+                let finished = self.take_row(row);
+                self.store(row, (key, value), provenance).ok();
+                row.set_table_number(self.table_number);
+
+                Some(finished)
+            }
+        }
+    }
+
+    /// Stores a value in the row, reporting values that could not be read.
+    /// A key the row already has is given back with its value.
+    fn store(
+        &mut self,
+        row: &mut Row,
+        field: (String, String),
+        provenance: Provenance,
+    ) -> Result<(), (String, String)> {
+        match row.set_field_from(field, Some(provenance)) {
+            Ok(()) => Ok(()),
+            Err(SetFieldError::NotStandard(key, value)) => {
+                self.warn(format!(
+                    "value {:?} of key {:?} could not be read, kept as typed",
+                    value, key
+                ));
+                Ok(())
+            }
+            Err(SetFieldError::Occupied(key, value)) => Err((key, value)),
+            Err(SetFieldError::NoKey(value)) => {
+                self.warn(format!("value {:?} has no key, it was left out", value));
+                Ok(())
+            }
+        }
+    }
+}
+
+/// The warning of a key that comes again in a record it does not end.
+fn repeated_in_record(key: &str) -> String {
+    format!(
+        "key {:?} is repeated, its value is added to the record",
+        key.trim()
+    )
+}
+
+lazy_static! {
+    /// Captions typed without a `SEQ` field.
+    static ref CAPTION: Regex = Regex::new(r"^Tabela \d+$").unwrap();
+    static ref TABLE_NUMBER: Regex = Regex::new(r"(?i)\btabela\s+(\d+)").unwrap();
+    static ref NUMBER: Regex = Regex::new(r"\d+").unwrap();
+}
+
+/// A table read inside a cell, `level` tables deep.
+#[derive(Debug, Default)]
+struct NestedTable {
+    level: usize,
+    lines: Vec<Vec<String>>,
+    cell: String,
+}
+
+impl NestedTable {
+    fn new(level: usize) -> Self {
+        Self {
+            level,
+            ..Default::default()
+        }
+    }
+
+    /// Separates the text of two paragraphs or cells of the cell.
+    fn new_line(&mut self) {
+        if !self.cell.is_empty() {
+            self.cell.push(' ');
+        }
+    }
+
+    fn end_cell(&mut self) {
+        let cell = take(&mut self.cell);
+
+        if let Some(line) = self.lines.last_mut() {
+            line.push(cell);
+        }
+    }
+}
+
+/// What the rows of a table are read as.
+enum Layout {
+    /// Rows are read as lists of cells until one tells the layout.
+    Unknown,
+    KeyValue,
+    Grid(Grid),
+}
+
+/// A Word field made of `w:fldChar` marks. Its result is read as any other
+/// text: the number of a `SEQ` or `PAGE` field, the text of a `HYPERLINK`,
+/// the bookmarked text of a `REF`.
+#[derive(Debug, Default)]
+struct ComplexField {
+    instruction: String,
+    /// Whether the separate mark was read.
+    result: bool,
+}
+
+/// Name of the field given by an instruction, in upper case (`SEQ`, `PAGE`,
+/// `HYPERLINK`, `REF`, ...).
+fn field_name(instruction: &str) -> Option<String> {
+    instruction
+        .split_whitespace()
+        .next()
+        .map(|name| name.to_uppercase())
+}
+
+pub enum RowOrParagraph {
+    Row(Row),
+    Paragraph(String),
+    Warning(Warning),
+}
+
+impl<R: Read> IntoIterator for ParseXml<R> {
+    type Item = Result<RowOrParagraph, CallError>;
+
+    type IntoIter = Rows<R>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        Rows {
+            parser: self,
+            pending: None,
+        }
+    }
+}
+
+pub struct Rows<R: Read> {
+    parser: ParseXml<R>,
+    pending: Option<Option<Result<RowOrParagraph, CallError>>>,
+}
+
+impl<R: Read> Iterator for Rows<R> {
+    type Item = Result<RowOrParagraph, CallError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pending.is_none() {
+            self.pending = Some(match self.parser.next_row() {
+                Ok(row) => Some(Ok(RowOrParagraph::Row(row))),
+                Err(CallError::Paragraph(p)) => Some(Ok(RowOrParagraph::Paragraph(p))),
+                Err(CallError::AlreadyFinished) => None,
+                Err(e) => Some(Err(e)),
+            });
+        }
+
+        // Warnings raised while reading an item come before the item itself.
+        if let Some(warning) = self.parser.take_warning() {
+            return Some(Ok(RowOrParagraph::Warning(warning)));
+        }
+
+        self.pending.take().flatten()
+    }
+}
+
+/// Opens a `.docx` archive and iterates over the rows and paragraphs of its
+/// main document, read with the default options.
+pub fn read_docx<R: Read + Seek>(r: R) -> Result<Rows<Cursor<Vec<u8>>>, CallError> {
+    read_document(r, Format::Docx, &ReadOptions::default())
+}
+
+/// Opens an `.odt` archive and iterates over the rows and paragraphs of its
+/// content, read with the default options.
+pub fn read_odt<R: Read + Seek>(r: R) -> Result<Rows<Cursor<Vec<u8>>>, CallError> {
+    read_document(r, Format::Odt, &ReadOptions::default())
+}
+
+pub fn read_document<R: Read + Seek>(
+    r: R,
+    format: Format,
+    options: &ReadOptions,
+) -> Result<Rows<Cursor<Vec<u8>>>, CallError> {
+    let mut zip = ZipArchive::new(r)?;
+    let mut document = Vec::new();
+
+    zip.by_name(format.entry())?.read_to_end(&mut document)?;
+
+    if options.revisions == RevisionMode::Fail {
+        let revisions = pending_revisions(Cursor::new(&document), format)?;
+
+        if !revisions.is_empty() {
+            return Err(CallError::Revisions(revisions));
+        }
+    }
+
+    let mut parser = ParseXml::with_options(Cursor::new(document), format, options.clone());
+
+    if let Some(entry) = format.comments_entry() {
+        if let Some(comments) = read_entry(&mut zip, entry)? {
+            parser.comments = read_comments(Cursor::new(comments))?;
+        }
+    }
+
+    for &(kind, entry) in format.notes_entries() {
+        if let Some(notes) = read_entry(&mut zip, entry)? {
+            parser.notes.extend(read_notes(Cursor::new(notes), kind)?);
+        }
+    }
+
+    Ok(parser.into_iter())
+}
+
+/// Reads an entry of the archive, if it has it.
+fn read_entry<R: Read + Seek>(
+    zip: &mut ZipArchive<R>,
+    entry: &str,
+) -> Result<Option<Vec<u8>>, CallError> {
+    match zip.by_name(entry) {
+        Ok(mut file) => {
+            let mut content = Vec::new();
+            file.read_to_end(&mut content)?;
+            Ok(Some(content))
+        }
+        Err(ZipError::FileNotFound) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Reads the comments of a Word document (`word/comments.xml`), by id.
+fn read_comments<R: Read>(r: R) -> Result<HashMap<String, Comment>, CallError> {
+    let comments = read_part(r, "comment")?
+        .into_iter()
+        .filter_map(|(attributes, text)| {
+            let comment = Comment {
+                author: attribute(&attributes, "author").map(str::to_owned),
+                date: attribute(&attributes, "date").map(str::to_owned),
+                text,
+            };
+
+            attribute(&attributes, "id").map(|id| (id.to_owned(), comment))
+        })
+        .collect();
+
+    Ok(comments)
+}
+
+/// Reads the footnotes or endnotes of a Word document
+/// (`word/footnotes.xml`, `word/endnotes.xml`), by kind and id. The
+/// separators Word keeps among them are left out.
+fn read_notes<R: Read>(
+    r: R,
+    kind: NoteKind,
+) -> Result<HashMap<(NoteKind, String), Note>, CallError> {
+    let element = match kind {
+        NoteKind::Footnote => "footnote",
+        NoteKind::Endnote => "endnote",
+    };
+
+    let notes = read_part(r, element)?
+        .into_iter()
+        .filter(|(attributes, _)| attribute(attributes, "type").is_none())
+        .filter_map(|(attributes, text)| {
+            attribute(&attributes, "id").map(|id| ((kind, id.to_owned()), Note { kind, text }))
+        })
+        .collect();
+
+    Ok(notes)
+}
+
+/// Reads the elements of a part of a Word document kept apart from the body
+/// (comments, footnotes), with their attributes and their text, whitespace
+/// collapsed.
+fn read_part<R: Read>(
+    r: R,
+    element: &str,
+) -> Result<Vec<(Vec<OwnedAttribute>, String)>, CallError> {
+    let mut items = Vec::new();
+    let mut current: Option<(Vec<OwnedAttribute>, String)> = None;
+
+    for event in EventReader::new(r) {
+        match event? {
+            XmlEvent::StartElement {
+                name, attributes, ..
+            } if name.local_name == element => {
+                current = Some((attributes, String::new()));
+            }
+            XmlEvent::StartElement {
+                name, attributes, ..
+            } => match Format::Docx.element(&name, &attributes) {
+                Element::Paragraph | Element::Text(true) | Element::Space(_) => {
+                    if let Some((_, text)) = current.as_mut() {
+                        text.push(' ');
+                    }
+                }
+                _ => {}
+            },
+            XmlEvent::EndElement { name } if name.local_name == element => {
+                if let Some((attributes, text)) = current.take() {
+                    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+                    items.push((attributes, text));
+                }
+            }
+            XmlEvent::Characters(text) => {
+                if let Some((_, current)) = current.as_mut() {
+                    current.push_str(&text);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(items)
+}
+
+/// A Word document of the body given.
+#[cfg(test)]
+fn docx(body: &str) -> String {
+    format!(
+        "<w:document xmlns:w=\"http://schemas.openxmlformats.org/wordprocessingml/2006/main\">\
+         <w:body>{}</w:body></w:document>",
+        body
+    )
+}
+
+/// A `.docx` archive of the body given and of the other parts given, by
+/// entry name.
+#[cfg(test)]
+fn docx_archive(body: &str, parts: &[(&str, &str)]) -> Vec<u8> {
+    use std::io::Write;
+    use std::iter;
+    use zip::ZipWriter;
+
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let document = docx(body);
+
+    for (name, content) in iter::once(&("word/document.xml", document.as_str())).chain(parts) {
+        zip.start_file(*name, Default::default()).unwrap();
+        zip.write_all(content.as_bytes()).unwrap();
+    }
+
+    zip.finish().unwrap().into_inner()
+}
+
+/// A cell of a single run of text, with its cell properties.
+#[cfg(test)]
+fn cell_with(properties: &str, text: &str) -> String {
+    format!(
+        "<w:tc><w:tcPr>{}</w:tcPr><w:p><w:r><w:t>{}</w:t></w:r></w:p></w:tc>",
+        properties, text
+    )
+}
+
+/// A cell of a single run of text.
+#[cfg(test)]
+fn cell(text: &str) -> String {
+    format!("<w:tc><w:p><w:r><w:t>{}</w:t></w:r></w:p></w:tc>", text)
+}
+
+/// A table row of plain cells.
+#[cfg(test)]
+fn row(cells: &[&str]) -> String {
+    let cells: String = cells.iter().map(|text| cell(text)).collect();
+    format!("<w:tr>{}</w:tr>", cells)
+}
+
+/// The items read from a Word document of the body given, with the default
+/// options.
+#[cfg(test)]
+fn read_items(body: &str) -> Vec<RowOrParagraph> {
+    read_items_with(body, ReadOptions::default())
+}
+
+#[cfg(test)]
+fn read_items_with(body: &str, options: ReadOptions) -> Vec<RowOrParagraph> {
+    ParseXml::with_options(docx(body).as_bytes(), Format::Docx, options)
+        .into_iter()
+        .collect::<Result<_, _>>()
+        .unwrap()
+}
+
+#[test]
+fn field_results() {
+    fn field(instruction: &str, result: &str) -> String {
+        format!(
+            "<w:r><w:fldChar w:fldCharType=\"begin\"/></w:r>\
+             <w:r><w:instrText xml:space=\"preserve\"> {} </w:instrText></w:r>\
+             <w:r><w:fldChar w:fldCharType=\"separate\"/></w:r>\
+             <w:r><w:t>{}</w:t></w:r>\
+             <w:r><w:fldChar w:fldCharType=\"end\"/></w:r>",
+            instruction, result
+        )
+    }
+
+    let body = format!(
+        "<w:p><w:r><w:t xml:space=\"preserve\">Tabela </w:t></w:r>{}</w:p>\
+         <w:p><w:r><w:t xml:space=\"preserve\">Tabela </w:t></w:r>{}</w:p>\
+         <w:tbl><w:tr>{}<w:tc><w:p>{}</w:p></w:tc></w:tr>\
+         <w:tr>{}<w:tc><w:p><w:r><w:t>p.</w:t></w:r>{}</w:p></w:tc></w:tr></w:tbl>",
+        field("REF _Ref1 \\h", "1"),
+        field("SEQ Tabela \\* ARABIC", "2"),
+        cell("Título completo do manuscrito"),
+        field("HYPERLINK \"https://example.org\"", "Revista"),
+        cell("Página"),
+        field("PAGE", "7"),
+    );
+
+    match &read_items(&body)[..] {
+        [RowOrParagraph::Paragraph(p), RowOrParagraph::Row(row)] => {
+            assert_eq!(p, "Tabela 1");
+            assert_eq!(row.table_number(), Some(2));
+            assert_eq!(row.get("title").unwrap().to_string(), "Revista");
+            assert_eq!(row.get("page").unwrap().to_string(), "p.~7");
+        }
+        _ => panic!("unexpected items"),
+    }
+}
+
+#[test]
+fn merged_cells() {
+    use crate::data::{Value, ValueType};
+
+    let span = "<w:gridSpan w:val=\"2\"/>";
+    let body = format!(
+        "<w:tbl><w:tr>{}</w:tr>{}<w:tr>{}</w:tr><w:tr>{}{}</w:tr><w:tr>{}{}</w:tr></w:tbl>",
+        cell_with(span, "Documentos doados"),
+        row(&["Título completo do manuscrito", "Revista"]),
+        cell_with(span, "e outros papéis"),
+        cell_with("<w:vMerge w:val=\"restart\"/>", "Quem doou?"),
+        cell("Abel"),
+        cell_with("<w:vMerge/>", ""),
+        cell("Tavares"),
+    );
+
+    match &read_items(&body)[..] {
+        [RowOrParagraph::Paragraph(p), RowOrParagraph::Row(row)] => {
+            assert_eq!(p, "Documentos doados");
+            assert_eq!(
+                row.get("title").unwrap().to_string(),
+                "Revista e outros papéis"
+            );
+            assert_eq!(row.get("doner").unwrap().to_string(), "Abel");
+            assert_eq!(
+                row.repeated("doner"),
+                [Value::parse(ValueType::Text, "Tavares")]
+            );
+            assert!(row.extras().is_empty());
+        }
+        _ => panic!("unexpected items"),
+    }
+}
+
+#[test]
+fn grid_table() {
+    let body = format!(
+        "<w:tbl>{}{}{}</w:tbl>",
+        row(&["Ano", "Página", "Observação"]),
+        row(&["1907", "p.1", "rasgado"]),
+        row(&["1908", "", ""]),
+    );
+
+    let rows: Vec<_> = read_items(&body)
+        .into_iter()
+        .filter_map(|item| match item {
+            RowOrParagraph::Row(row) => Some(row),
+            _ => None,
+        })
+        .collect();
+
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0].get("year").unwrap().to_string(), "1907");
+    assert_eq!(rows[0].get("page").unwrap().to_string(), "p.~1");
+    assert_eq!(
+        rows[0].extras(),
+        &[("Observação".to_owned(), "rasgado".to_owned())]
+    );
+    assert_eq!(rows[1].get("year").unwrap().to_string(), "1908");
+    assert!(rows[1].get("page").is_none());
+    // Two cells are a key and its value, even when the value looks like a
+    // key: "anônimo" starts like "ano".
+    let body = format!(
+        "<w:tbl>{}{}{}</w:tbl>",
+        row(&["Quem doou?", "Anônimo"]),
+        row(&["Tipo de documento", "Ata"]),
+        row(&["Ano da Revista", "1907"]),
+    );
+
+    match &read_items(&body)[..] {
+        [RowOrParagraph::Row(row)] => {
+            assert_eq!(row.get("doner").unwrap().to_string(), "Anônimo");
+            assert_eq!(row.get("year").unwrap().to_string(), "1907");
+            assert!(row.get("kind").is_some());
+        }
+        items => panic!("{} items", items.len()),
+    }
+}
+
+#[test]
+fn nested_table() {
+    let nested = format!("<w:tbl>{}{}</w:tbl>", row(&["Carta"]), row(&["Ofício"]));
+    let body = format!(
+        "<w:tbl><w:tr>{}<w:tc><w:p><w:r><w:t>Maço com:</w:t></w:r></w:p>{}<w:p/></w:tc></w:tr>\
+         {}</w:tbl>",
+        cell("Título completo do manuscrito"),
+        nested,
+        row(&["Página", "p.4"]),
+    );
+
+    match &read_items(&body)[..] {
+        [RowOrParagraph::Row(row)] => {
+            assert_eq!(row.get("title").unwrap().to_string(), "Maço com:");
+            assert_eq!(
+                row.sub_table("title").unwrap().lines(),
+                &[vec!["Carta".to_owned()], vec!["Ofício".to_owned()]]
+            );
+            assert_eq!(row.get("page").unwrap().to_string(), "p.~4");
+        }
+        _ => panic!("unexpected items"),
+    }
+}
+
+#[test]
+fn formatted_text() {
+    fn run(text: &str, properties: &str) -> String {
+        format!(
+            "<w:r><w:rPr>{}</w:rPr><w:t xml:space=\"preserve\">{}</w:t></w:r>",
+            properties, text
+        )
+    }
+
+    // The properties replaced by a tracked change are not the text's.
+    let body = format!(
+        "<w:tbl><w:tr>{}<w:tc><w:p>{}{}{}</w:p></w:tc></w:tr>\
+         <w:tr>{}<w:tc><w:p>{}{}{}</w:p></w:tc></w:tr></w:tbl>",
+        cell("Título completo do manuscrito"),
+        run("Carta ao", ""),
+        run("Diário", "<w:i/>"),
+        run(
+            "de Lisboa",
+            "<w:rPrChange w:id=\"1\" w:author=\"Maria\"><w:rPr><w:i/></w:rPr></w:rPrChange>"
+        ),
+        cell_with(
+            "<w:tcPrChange w:id=\"2\" w:author=\"Maria\">\
+             <w:tcPr><w:gridSpan w:val=\"2\"/></w:tcPr></w:tcPrChange>",
+            "Quem doou?"
+        ),
+        run("1", ""),
+        run("o", "<w:vertAlign w:val=\"superscript\"/>"),
+        run("secretário", "<w:b w:val=\"0\"/>"),
+    );
+
+    let italic = Style {
+        italic: true,
+        ..Style::default()
+    };
+    let superscript = Style {
+        superscript: true,
+        ..Style::default()
+    };
+
+    match &read_items(&body)[..] {
+        [RowOrParagraph::Row(row)] => {
+            let title = row.rich_text("title").unwrap();
+            assert_eq!(title.spans()[1].style, italic);
+            assert_eq!(title.spans()[1].text.trim(), "Diário");
+            assert!(title.spans()[2].style.is_plain());
+            assert_eq!(title.spans()[2].text.trim(), "de Lisboa");
+
+            let doner = row.rich_text("doner").unwrap();
+            assert_eq!(doner.spans()[1].style, superscript);
+            assert_eq!(doner.spans()[1].text.trim(), "o");
+            assert!(doner.spans()[2].style.is_plain());
+        }
+        _ => panic!("unexpected items"),
+    }
+}
+
+#[test]
+fn tracked_changes() {
+    let body = format!(
+        "<w:tbl><w:tr>{}<w:tc><w:p><w:del w:author=\"João\" w:date=\"2024-03-02T11:00:00Z\">\
+         <w:r><w:delText>1903</w:delText></w:r></w:del>\
+         <w:ins w:author=\"Maria\"><w:r><w:t>1904</w:t></w:r></w:ins></w:p></w:tc></w:tr>\
+         </w:tbl>",
+        cell("Ano da Revista")
+    );
+
+    let year = |revisions| {
+        let options = ReadOptions {
+            revisions,
+            ..ReadOptions::default()
+        };
+
+        match &read_items_with(&body, options)[..] {
+            [RowOrParagraph::Row(row)] => row.get("year").unwrap().to_string(),
+            _ => panic!("unexpected items"),
+        }
+    };
+
+    assert_eq!(year(RevisionMode::Accept), "1904");
+    assert_eq!(year(RevisionMode::Reject), "1903");
+
+    let revisions = pending_revisions(docx(&body).as_bytes(), Format::Docx).unwrap();
+
+    assert_eq!(
+        revisions
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>(),
+        [
+            "deletion of \"1903\" by João on 2024-03-02T11:00:00Z",
+            "insertion of \"1904\" by Maria",
+        ]
+    );
+
+    // A parser of the body alone stops at the first revision.
+    let options = ReadOptions {
+        revisions: RevisionMode::Fail,
+        ..ReadOptions::default()
+    };
+    let items: Vec<_> = ParseXml::with_options(docx(&body).as_bytes(), Format::Docx, options)
+        .into_iter()
+        .collect();
+
+    match &items[..] {
+        [Err(CallError::Revisions(refused))] => assert_eq!(refused[..], revisions[..1]),
+        _ => panic!("{} items", items.len()),
+    }
+
+    // OpenDocument tracked changes are reported, the text being read as shown.
+    let content = "<office:document-content \
+         xmlns:office=\"urn:oasis:names:tc:opendocument:xmlns:office:1.0\" \
+         xmlns:table=\"urn:oasis:names:tc:opendocument:xmlns:table:1.0\" \
+         xmlns:text=\"urn:oasis:names:tc:opendocument:xmlns:text:1.0\">\
+         <office:body><office:text><text:tracked-changes>\
+         <text:changed-region text:id=\"ct1\"><text:deletion><text:p>1903</text:p>\
+         </text:deletion></text:changed-region></text:tracked-changes><table:table>\
+         <table:table-row><table:table-cell><text:p>Ano da Revista</text:p></table:table-cell>\
+         <table:table-cell><text:p><text:change text:change-id=\"ct1\"/>1904</text:p>\
+         </table:table-cell></table:table-row></table:table></office:text></office:body>\
+         </office:document-content>";
+
+    for revisions in [
+        RevisionMode::Accept,
+        RevisionMode::Reject,
+        RevisionMode::Fail,
+    ] {
+        let options = ReadOptions {
+            revisions,
+            ..ReadOptions::default()
+        };
+        let items: Vec<_> = ParseXml::with_options(content.as_bytes(), Format::Odt, options)
+            .into_iter()
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        match &items[..] {
+            [RowOrParagraph::Warning(warning), RowOrParagraph::Row(row)] => {
+                assert!(warning
+                    .message
+                    .contains("tracked changes are not supported"));
+                assert_eq!(row.get("year").unwrap().to_string(), "1904");
+            }
+            _ => panic!("{} items", items.len()),
+        }
+    }
+}
+
+#[test]
+fn comments() {
+    let comments = "<w:comments xmlns:w=\"http://schemas.openxmlformats.org/wordprocessingml/2006/main\">\
+         <w:comment w:id=\"1\" w:author=\"Maria\" w:date=\"2024-03-01T10:00:00Z\">\
+         <w:p><w:r><w:t>confirmar data</w:t></w:r></w:p><w:p><w:r><w:t>na ata original</w:t></w:r></w:p>\
+         </w:comment></w:comments>";
+    let body = format!(
+        "<w:tbl><w:tr>{}<w:tc><w:p><w:commentRangeStart w:id=\"1\"/><w:r><w:t>p.7</w:t></w:r>\
+         <w:commentRangeEnd w:id=\"1\"/><w:r><w:commentReference w:id=\"1\"/></w:r></w:p></w:tc></w:tr>\
+         </w:tbl>",
+        cell("Página")
+    );
+
+    let archive = docx_archive(&body, &[("word/comments.xml", comments)]);
+    let items: Vec<_> = read_docx(Cursor::new(archive))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    let row = match &items[..] {
+        [RowOrParagraph::Row(row)] => row,
+        _ => panic!("{} items", items.len()),
+    };
+
+    assert_eq!(
+        row.field_comments("page").collect::<Vec<_>>(),
+        [&Comment {
+            author: Some("Maria".to_owned()),
+            date: Some("2024-03-01T10:00:00Z".to_owned()),
+            text: "confirmar data na ata original".to_owned(),
+        }]
+    );
+}
+
+#[test]
+fn notes() {
+    let footnotes = "<w:footnotes xmlns:w=\"http://schemas.openxmlformats.org/wordprocessingml/2006/main\">\
+         <w:footnote w:type=\"separator\" w:id=\"-1\"><w:p><w:r><w:separator/></w:r></w:p></w:footnote>\
+         <w:footnote w:id=\"1\"><w:p><w:r><w:footnoteRef/></w:r><w:r><w:t xml:space=\"preserve\"> Atribuição de Inocêncio.</w:t></w:r></w:p></w:footnote>\
+         </w:footnotes>";
+    let endnotes = "<w:endnotes xmlns:w=\"http://schemas.openxmlformats.org/wordprocessingml/2006/main\">\
+         <w:endnote w:type=\"separator\" w:id=\"-1\"><w:p><w:r><w:separator/></w:r></w:p></w:endnote>\
+         <w:endnote w:id=\"1\"><w:p><w:r><w:endnoteRef/></w:r><w:r><w:t>Exemplar incompleto.</w:t></w:r></w:p></w:endnote>\
+         </w:endnotes>";
+    let body = format!(
+        "<w:tbl><w:tr>{}<w:tc><w:p><w:r><w:t>O Panorama</w:t></w:r>\
+         <w:r><w:footnoteReference w:id=\"1\"/></w:r></w:p></w:tc></w:tr>\
+         <w:tr>{}<w:tc><w:p><w:r><w:t>p.7</w:t></w:r>\
+         <w:r><w:endnoteReference w:id=\"1\"/></w:r></w:p></w:tc></w:tr></w:tbl>",
+        cell("Título"),
+        cell("Página")
+    );
+
+    let archive = docx_archive(
+        &body,
+        &[
+            ("word/footnotes.xml", footnotes),
+            ("word/endnotes.xml", endnotes),
+        ],
+    );
+    let items: Vec<_> = read_docx(Cursor::new(archive))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    let row = match &items[..] {
+        [RowOrParagraph::Row(row)] => row,
+        _ => panic!("{} items", items.len()),
+    };
+
+    // Footnotes and endnotes of the same id are not mixed up.
+    assert_eq!(row.get("title").unwrap().to_string(), "O Panorama");
+    assert_eq!(
+        row.field_notes("title").collect::<Vec<_>>(),
+        [&Note {
+            kind: NoteKind::Footnote,
+            text: "Atribuição de Inocêncio.".to_owned(),
+        }]
+    );
+    assert_eq!(row.get("page").unwrap().to_string(), "p.~7");
+    assert_eq!(
+        row.field_notes("page").collect::<Vec<_>>(),
+        [&Note {
+            kind: NoteKind::Endnote,
+            text: "Exemplar incompleto.".to_owned(),
+        }]
+    );
+}
+
+#[test]
+fn resync() {
+    let body = format!(
+        "<w:tbl>{}{}</w:tbl><w:tbl>{}</w:tbl>",
+        row(&["Ano da Revista", "1905", "extra"]),
+        row(&["Página", "p.3"]),
+        row(&["Ano da Revista", "1906"]),
+    );
+    let document = docx(&body);
+
+    let items: Vec<_> = ParseXml::new(document.as_bytes()).into_iter().collect();
+
+    match &items[..] {
+        [Err(CallError::Parse(_)), Ok(RowOrParagraph::Row(row))] => {
+            assert_eq!(row.get("year").unwrap().to_string(), "1906");
+            assert!(row.get("page").is_none());
+        }
+        _ => panic!("unexpected items"),
+    }
+}
+
+#[test]
+fn docx_odt_parity() {
+    fn odt_cell(attributes: &str, text: &str) -> String {
+        format!(
+            "<table:table-cell {}><text:p>{}</text:p></table:table-cell>",
+            attributes, text
+        )
+    }
+
+    let docx_body = format!(
+        "<w:p><w:r><w:t>Tabela 3</w:t></w:r></w:p><w:tbl><w:tr>{}</w:tr>{}{}\
+         <w:tr>{}{}</w:tr><w:tr>{}{}</w:tr></w:tbl>",
+        cell_with("<w:gridSpan w:val=\"2\"/>", "Documentos doados"),
+        row(&["Ano da Revista", "1907"]),
+        row(&["Página", "p.12"]),
+        cell_with("<w:vMerge w:val=\"restart\"/>", "Quem doou?"),
+        cell("Abel"),
+        cell_with("<w:vMerge/>", ""),
+        cell("Tavares"),
+    );
+    let odt_content = format!(
+        "<office:document-content \
+         xmlns:office=\"urn:oasis:names:tc:opendocument:xmlns:office:1.0\" \
+         xmlns:table=\"urn:oasis:names:tc:opendocument:xmlns:table:1.0\" \
+         xmlns:text=\"urn:oasis:names:tc:opendocument:xmlns:text:1.0\">\
+         <office:body><office:text><text:p>Tabela 3</text:p><table:table>\
+         <table:table-row>{}<table:covered-table-cell/></table:table-row>\
+         <table:table-row>{}{}</table:table-row><table:table-row>{}{}</table:table-row>\
+         <table:table-row>{}{}</table:table-row>\
+         <table:table-row><table:covered-table-cell/>{}</table:table-row>\
+         </table:table></office:text></office:body></office:document-content>",
+        odt_cell("table:number-columns-spanned=\"2\"", "Documentos doados"),
+        odt_cell("", "Ano da Revista"),
+        odt_cell("", "1907"),
+        odt_cell("", "Página"),
+        odt_cell("", "p.12"),
+        odt_cell("table:number-rows-spanned=\"2\"", "Quem doou?"),
+        odt_cell("", "Abel"),
+        odt_cell("", "Tavares"),
+    );
+
+    let odt: Vec<_> = ParseXml::with_format(odt_content.as_bytes(), Format::Odt)
+        .into_iter()
+        .collect::<Result<_, _>>()
+        .unwrap();
+
+    match (&read_items(&docx_body)[..], &odt[..]) {
+        (
+            [RowOrParagraph::Paragraph(h1), RowOrParagraph::Row(r1)],
+            [RowOrParagraph::Paragraph(h2), RowOrParagraph::Row(r2)],
+        ) => {
+            assert_eq!(h1, "Documentos doados");
+            assert_eq!(h1, h2);
+            assert_eq!(r1, r2);
+            assert_eq!(r1.table_number(), Some(3));
+            assert_eq!(r1.joined("doner", "Abel".to_owned()), "Abel; Tavares");
+        }
+        (docx, odt) => panic!("{} items from docx, {} from odt", docx.len(), odt.len()),
+    }
+}
+
+#[test]
+fn record_boundaries() {
+    let body = format!(
+        "<w:tbl>{}{}{}{}{}</w:tbl><w:tbl>{}</w:tbl>",
+        row(&["Ano da Revista", "1905"]),
+        row(&["Título completo do manuscrito", "A"]),
+        row(&["Ano da Revista", "1906"]),
+        row(&["", ""]),
+        row(&["Título completo do manuscrito", "B"]),
+        row(&["Página", "p.3"]),
+    );
+
+    // The values of each record read, in schema order.
+    let records = |boundary: &str| -> Vec<String> {
+        let options = ReadOptions {
+            boundary: boundary.parse().unwrap(),
+            ..ReadOptions::default()
+        };
+
+        read_items_with(&body, options)
+            .into_iter()
+            .filter_map(|item| match item {
+                RowOrParagraph::Row(row) => {
+                    let values: Vec<_> = row.plain_values().into_iter().flatten().collect();
+                    Some(values.join(", "))
+                }
+                _ => None,
+            })
+            .collect()
+    };
+
+    assert_eq!(records("duplicate"), ["1905, A", "1906, B", "3"]);
+    assert_eq!(records("table"), ["1905; 1906, A; B", "3"]);
+    assert_eq!(records("blank"), ["1905, A", "1906", "B", "3"]);
+    assert_eq!(records("key:year"), ["1905, A", "1906, 3, B"]);
+
+    // With a record key, another repeated key does not split the record.
+    let body = format!(
+        "<w:tbl>{}{}{}{}</w:tbl>",
+        row(&["Ano da Revista", "1905"]),
+        row(&["Título completo do manuscrito", "A"]),
+        row(&["Título completo do manuscrito", "B"]),
+        row(&["Ano da Revista", "1906"]),
+    );
+    let options = ReadOptions {
+        boundary: "key:year".parse().unwrap(),
+        ..ReadOptions::default()
+    };
+
+    match &read_items_with(&body, options)[..] {
+        [RowOrParagraph::Warning(warning), RowOrParagraph::Row(first), RowOrParagraph::Row(second)] =>
+        {
+            assert_eq!(
+                warning.message,
+                repeated_in_record("Título completo do manuscrito")
+            );
+            assert_eq!(first.joined("title", "A".to_owned()), "A; B");
+            assert_eq!(second.get("year").unwrap().to_string(), "1906");
+        }
+        items => panic!("{} items", items.len()),
+    }
+}
+
+#[test]
+fn provenance() {
+    let body = format!(
+        "<w:p><w:r><w:t>Tabela 4</w:t></w:r></w:p><w:tbl>{}{}{}</w:tbl>",
+        row(&["Ano da Revista", "1905"]),
+        row(&["Título completo do manuscrito", "Revista  do IAGP"]),
+        row(&["Observação", "rasgada"]),
+    );
+    let options = ReadOptions {
+        file: Some("catalogo.docx".into()),
+        ..ReadOptions::default()
+    };
+
+    // The extra key is reported, the row comes after the warning.
+    let items = read_items_with(&body, options);
+    let row = match &items[..] {
+        [RowOrParagraph::Warning(_), RowOrParagraph::Row(row)] => row,
+        items => panic!("{} items", items.len()),
+    };
+
+    let title = row.provenance("title").unwrap();
+    assert_eq!(
+        title.to_string(),
+        "catalogo.docx, table 1 (Tabela 4), row 2"
+    );
+    assert_eq!(title.raw, "Revista  do IAGP");
+    assert_eq!(row.provenance("Observação").unwrap().row, 3);
+    assert_eq!(row.provenances().count(), 3);
+
+    let json = serde_json::to_value(row).unwrap();
+    assert_eq!(
+        json["provenance"]["year"],
+        serde_json::json!({
+            "file": "catalogo.docx",
+            "table": 1,
+            "row": 1,
+            "caption": "Tabela 4",
+            "raw": "1905"
+        })
+    );
+    assert_eq!(json["provenance"]["Observação"]["raw"], "rasgada");
+}
+
+#[test]
+fn table_numbers() {
+    let caption = |text: &str| format!("<w:p><w:r><w:t>{}</w:t></w:r></w:p>", text);
+    let table = |year: &str| format!("<w:tbl>{}</w:tbl>", row(&["Ano da Revista", year]));
+    let body = [
+        caption("Tabela 1"),
+        table("1901"),
+        caption("Tabela 3"),
+        table("1903"),
+        caption("Tabela 3"),
+        table("1904"),
+        caption("Tabela 7"),
+        table("1907"),
+        // A caption under its table, then text: the next table has none.
+        caption("Tabela 8"),
+        caption("Fonte: acervo do Instituto"),
+        table("1908"),
+        // The year typed after the number of a caption is not its number.
+        "<w:p><w:r><w:t xml:space=\"preserve\">Tabela </w:t></w:r>\
+         <w:fldSimple w:instr=\" SEQ Tabela \\* ARABIC \"><w:r><w:t>9</w:t></w:r></w:fldSimple>\
+         <w:r><w:t xml:space=\"preserve\"> – Revista do IAGP, 1903</w:t></w:r></w:p>"
+            .to_owned(),
+        table("1909"),
+        // Numbers too large to follow another are not added to.
+        caption("Tabela 4294967295"),
+        table("1910"),
+        caption("Tabela 10"),
+        table("1911"),
+    ]
+    .concat();
+
+    let mut numbers = Vec::new();
+    let mut warnings = Vec::new();
+
+    for item in read_items(&body) {
+        match item {
+            RowOrParagraph::Row(row) => numbers.push(row.table_number()),
+            RowOrParagraph::Warning(w) => warnings.push(w.message),
+            RowOrParagraph::Paragraph(_) => {}
+        }
+    }
+
+    assert_eq!(
+        numbers,
+        [
+            Some(1),
+            Some(3),
+            Some(3),
+            Some(7),
+            None,
+            Some(9),
+            Some(u32::MAX),
+            Some(10)
+        ]
+    );
+    assert_eq!(
+        warnings,
+        [
+            "caption \"Tabela 3\" skips table number 2",
+            "caption \"Tabela 3\" repeats table number 3",
+            "caption \"Tabela 7\" skips table numbers 4 to 6",
+            "caption \"Tabela 4294967295\" skips table numbers 10 to 4294967294",
+        ]
+    );
+}
+
+#[test]
+fn grid_without_fields() {
+    let body = format!(
+        "<w:tbl>{}{}{}</w:tbl>",
+        row(&["Coluna", "Outra"]),
+        row(&["a", "b"]),
+        row(&["c", "d"]),
+    );
+    let options = ReadOptions {
+        layout: TableLayout::Grid,
+        ..ReadOptions::default()
+    };
+
+    match &read_items_with(&body, options)[..] {
+        [RowOrParagraph::Warning(w)] => assert_eq!(
+            w.message,
+            "no heading names a field, the rows of the table were skipped"
+        ),
+        items => panic!("{} items", items.len()),
+    }
+}
+
+#[test]
+fn annotations() {
+    use crate::annotation::{AnnotationRules, Mark, MarkMeaning};
+
+    let run = |properties: &str, text: &str| {
+        format!(
+            "<w:r><w:rPr>{}</w:rPr><w:t xml:space=\"preserve\">{}</w:t></w:r>",
+            properties, text
+        )
+    };
+    let docx_row = |key: &str, runs: String| {
+        format!("<w:tr>{}<w:tc><w:p>{}</w:p></w:tc></w:tr>", cell(key), runs)
+    };
+    let docx_body = format!(
+        "<w:tbl>{}{}{}</w:tbl>",
+        docx_row(
+            "Título completo do manuscrito",
+            run("<w:highlight w:val=\"yellow\"/>", "Revista")
+        ),
+        // A preserved run starts with a space of its own.
+        docx_row(
+            "Quem doou?",
+            run("", "Abel") + &run("<w:strike/>", "Tavares")
+        ),
+        docx_row(
+            "Página",
+            run("<w:shd w:val=\"clear\" w:fill=\"FFFF00\"/>", "p.3")
+        ),
+    );
+
+    // OpenDocument has no run shading, only highlight and strike.
+    let odt_row = |key: &str, text: &str| {
+        format!(
+            "<table:table-row><table:table-cell><text:p>{}</text:p></table:table-cell>\
+             <table:table-cell><text:p>{}</text:p></table:table-cell></table:table-row>",
+            key, text
+        )
+    };
+    let odt_content = format!(
+        "<office:document-content \
+         xmlns:office=\"urn:oasis:names:tc:opendocument:xmlns:office:1.0\" \
+         xmlns:style=\"urn:oasis:names:tc:opendocument:xmlns:style:1.0\" \
+         xmlns:table=\"urn:oasis:names:tc:opendocument:xmlns:table:1.0\" \
+         xmlns:text=\"urn:oasis:names:tc:opendocument:xmlns:text:1.0\" \
+         xmlns:fo=\"urn:oasis:names:tc:opendocument:xmlns:xsl-fo-compatible:1.0\">\
+         <office:automatic-styles>\
+         <style:style style:name=\"T1\" style:family=\"text\">\
+         <style:text-properties fo:background-color=\"#ffff00\"/></style:style>\
+         <style:style style:name=\"T2\" style:family=\"text\">\
+         <style:text-properties style:text-line-through-style=\"solid\"/></style:style>\
+         </office:automatic-styles>\
+         <office:body><office:text><table:table>{}{}{}</table:table></office:text></office:body>\
+         </office:document-content>",
+        odt_row(
+            "Título completo do manuscrito",
+            "<text:span text:style-name=\"T1\">Revista</text:span>"
+        ),
+        odt_row(
+            "Quem doou?",
+            "Abel <text:span text:style-name=\"T2\">Tavares</text:span>"
+        ),
+        odt_row("Página", "p.3"),
+    );
+
+    let read = |format: Format, annotations: &AnnotationRules| {
+        let options = ReadOptions {
+            annotations: annotations.clone(),
+            ..ReadOptions::default()
+        };
+        let document = match format {
+            Format::Docx => docx(&docx_body),
+            Format::Odt => odt_content.clone(),
+        };
+        let items: Vec<_> = ParseXml::with_options(document.as_bytes(), format, options)
+            .into_iter()
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        match items.into_iter().next() {
+            Some(RowOrParagraph::Row(row)) => row,
+            item => panic!("{:?}", item.map(|_| "not a row")),
+        }
+    };
+    let text = |row: &Row, name| row.get(name).unwrap().to_string();
+
+    // Highlighted values are to be verified, struck text is removed.
+    let rules = AnnotationRules::default();
+
+    for format in [Format::Docx, Format::Odt] {
+        let row = read(format, &rules);
+
+        assert_eq!(text(&row, "title"), "Revista");
+        assert!(row.state("title").unwrap().to_verify);
+        assert_eq!(text(&row, "doner"), "Abel");
+        assert_eq!(
+            row.state("doner").unwrap().removed.as_deref(),
+            Some("Tavares")
+        );
+        assert!(row.state("page").is_none());
+    }
+
+    // As with `--mark highlight=ignore --mark strike=verify --mark
+    // shading=verify`.
+    let mut rules = AnnotationRules::default();
+    rules.set(Mark::Highlight, MarkMeaning::Ignore);
+    rules.set(Mark::Strike, MarkMeaning::Verify);
+    rules.set(Mark::Shading, MarkMeaning::Verify);
+
+    for format in [Format::Docx, Format::Odt] {
+        let row = read(format, &rules);
+
+        assert!(row.state("title").is_none());
+        assert!(row.rich_text("title").unwrap().spans()[0].style.highlight);
+        assert_eq!(text(&row, "doner"), "Abel Tavares");
+        assert!(row.state("doner").unwrap().to_verify);
+        assert!(row.state("doner").unwrap().removed.is_none());
+        assert_eq!(row.state("page").is_some(), format == Format::Docx);
+    }
+}
+
+#[test]
+fn value_without_key() {
+    let body = format!(
+        "<w:tbl>{}{}{}{}{}</w:tbl>",
+        row(&["Ano da Revista", "1903"]),
+        row(&["", "anotação solta"]),
+        row(&["Página", "p.7"]),
+        row(&[":", "outra anotação"]),
+        row(&["Título", "O Panorama"]),
+    );
+
+    match &read_items(&body)[..] {
+        [RowOrParagraph::Warning(first), RowOrParagraph::Warning(second), RowOrParagraph::Row(row)] =>
+        {
+            assert_eq!(
+                first.message,
+                "value \"anotação solta\" has no key, it was left out"
+            );
+            assert_eq!(
+                second.message,
+                "value \"outra anotação\" has no key, it was left out"
+            );
+            assert!(row.extras().is_empty());
+            assert_eq!(row.get("title").unwrap().to_string(), "O Panorama");
+        }
+        items => panic!("{} items", items.len()),
+    }
+}
+
+#[test]
+fn grid_spans() {
+    fn odt_cell(attributes: &str, text: &str) -> String {
+        format!(
+            "<table:table-cell {}><text:p>{}</text:p></table:table-cell>",
+            attributes, text
+        )
+    }
+
+    // The title spans the title and page columns of the first record, and
+    // the doner cell is merged over both records.
+    let docx_body = format!(
+        "<w:tbl>{}<w:tr>{}{}{}</w:tr><w:tr>{}{}{}{}</w:tr></w:tbl>",
+        row(&["Ano", "Título", "Página", "Quem doou?"]),
+        cell("1907"),
+        cell_with("<w:gridSpan w:val=\"2\"/>", "O Panorama"),
+        cell_with("<w:vMerge w:val=\"restart\"/>", "Abel"),
+        cell("1908"),
+        cell("A Ilustração"),
+        cell("p.2"),
+        cell_with("<w:vMerge/>", ""),
+    );
+    let odt_content = format!(
+        "<office:document-content \
+         xmlns:office=\"urn:oasis:names:tc:opendocument:xmlns:office:1.0\" \
+         xmlns:table=\"urn:oasis:names:tc:opendocument:xmlns:table:1.0\" \
+         xmlns:text=\"urn:oasis:names:tc:opendocument:xmlns:text:1.0\">\
+         <office:body><office:text><table:table>\
+         <table:table-row>{}{}{}{}</table:table-row>\
+         <table:table-row>{}{}<table:covered-table-cell/>{}</table:table-row>\
+         <table:table-row>{}{}{}<table:covered-table-cell/></table:table-row>\
+         </table:table></office:text></office:body></office:document-content>",
+        odt_cell("", "Ano"),
+        odt_cell("", "Título"),
+        odt_cell("", "Página"),
+        odt_cell("", "Quem doou?"),
+        odt_cell("", "1907"),
+        odt_cell("table:number-columns-spanned=\"2\"", "O Panorama"),
+        odt_cell("table:number-rows-spanned=\"2\"", "Abel"),
+        odt_cell("", "1908"),
+        odt_cell("", "A Ilustração"),
+        odt_cell("", "p.2"),
+    );
+
+    let rows = |items: Vec<RowOrParagraph>| -> Vec<Row> {
+        items
+            .into_iter()
+            .filter_map(|item| match item {
+                RowOrParagraph::Row(row) => Some(row),
+                _ => None,
+            })
+            .collect()
+    };
+    let docx = rows(read_items(&docx_body));
+    let odt = rows(
+        ParseXml::with_format(odt_content.as_bytes(), Format::Odt)
+            .into_iter()
+            .collect::<Result<_, _>>()
+            .unwrap(),
+    );
+
+    assert_eq!(docx, odt);
+    assert_eq!(docx.len(), 2);
+    assert_eq!(docx[0].get("title").unwrap().to_string(), "O Panorama");
+    assert!(docx[0].get("page").is_none());
+    assert_eq!(docx[0].get("doner").unwrap().to_string(), "Abel");
+    assert_eq!(docx[1].get("page").unwrap().to_string(), "p.~2");
+    assert_eq!(docx[1].get("doner").unwrap().to_string(), "Abel");
+}
@@ -0,0 +1,198 @@
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use crate::data::{Provenance, Row, Schema, SetFieldError};
+use crate::diagnostic::{ambiguous_key, Location, Warning};
+use crate::{ReadOptions, RowOrParagraph};
+
+/// The headings of a grid, named like the keys of the document tables, which
+/// give the field of each column.
+pub(crate) struct Grid {
+    schema: Rc<Schema>,
+    headings: Vec<String>,
+}
+
+impl Grid {
+    /// Reads the heading line. Headings that name no field, or more than one,
+    /// are reported, and so is a line where no heading names a field: its
+    /// table gives no rows (see [`names_fields`](Self::names_fields)).
+    /// `location` turns a column into a `Location`.
+    pub(crate) fn new<L>(
+        schema: Rc<Schema>,
+        headings: Vec<String>,
+        location: L,
+        warnings: &mut Vec<Warning>,
+    ) -> Self
+    where
+        L: Fn(usize) -> Location,
+    {
+        let grid = Self { schema, headings };
+
+        if !grid.names_fields() {
+            warnings.push(Warning {
+                location: location(0),
+                message: "no heading names a field, the rows of the table were skipped".to_owned(),
+            });
+
+            return grid;
+        }
+
+        let Self { schema, headings } = &grid;
+
+        for (col, heading) in headings.iter().enumerate() {
+            if schema.classify(heading).is_none() && !heading.trim().is_empty() {
+                warnings.push(Warning {
+                    location: location(col),
+                    message: format!(
+                        "column {:?} names no field, kept as an extra field",
+                        heading
+                    ),
+                });
+            }
+
+            if let Some(message) = ambiguous_key(schema, heading) {
+                warnings.push(Warning {
+                    location: location(col),
+                    message,
+                });
+            }
+        }
+
+        grid
+    }
+
+    /// Whether a line holds the headings of a grid rather than a key and its
+    /// value: it has more than two cells, and two of them at least name
+    /// fields. A line of two cells is a key and its value even when the value
+    /// looks like a key ("Quem doou? | Anônimo").
+    pub(crate) fn is_heading(schema: &Schema, cells: &[String]) -> bool {
+        let cells: Vec<_> = cells
+            .iter()
+            .filter(|cell| !cell.trim().is_empty())
+            .collect();
+
+        cells.len() > 2
+            && cells
+                .iter()
+                .filter(|cell| schema.classify(cell).is_some())
+                .count()
+                >= 2
+    }
+
+    /// Whether some heading names a field. Grids where none does give no
+    /// rows.
+    pub(crate) fn names_fields(&self) -> bool {
+        self.headings
+            .iter()
+            .any(|heading| self.schema.classify(heading).is_some())
+    }
+
+    /// The heading of a column, if it has one.
+    pub(crate) fn heading(&self, col: usize) -> Option<&str> {
+        self.headings
+            .get(col)
+            .map(String::as_str)
+            .filter(|heading| !heading.trim().is_empty())
+    }
+
+    /// Builds the row of a record line. The columns of unknown headings are
+    /// kept as extra fields, columns without heading are ignored and empty
+    /// cells leave the field unset.
+    pub(crate) fn record<L>(
+        &self,
+        file: Option<&PathBuf>,
+        cells: Vec<String>,
+        location: L,
+        warnings: &mut Vec<Warning>,
+    ) -> Row
+    where
+        L: Fn(usize) -> Location,
+    {
+        let mut row = Row::new(self.schema.clone());
+
+        for (col, (heading, value)) in self.headings.iter().zip(cells).enumerate() {
+            if heading.trim().is_empty() || value.trim().is_empty() {
+                continue;
+            }
+
+            let at = location(col);
+            let provenance = Provenance {
+                file: file.cloned(),
+                table: at.table,
+                row: at.row,
+                caption: at.caption.clone(),
+                raw: value.clone(),
+            };
+
+            let message = match row.set_field_from((heading.clone(), value), Some(provenance)) {
+                Ok(()) => continue,
+                Err(SetFieldError::Occupied(key, _)) => {
+                    format!("field of column {:?} was already set", key)
+                }
+                Err(SetFieldError::NotStandard(key, value)) => format!(
+                    "value {:?} of column {:?} could not be read, kept as typed",
+                    value, key
+                ),
+                Err(SetFieldError::NoKey(value)) => {
+                    format!("value {:?} of a column without heading was left out", value)
+                }
+            };
+
+            warnings.push(Warning {
+                location: at,
+                message,
+            });
+        }
+
+        row
+    }
+}
+
+/// Builds rows out of a grid whose first non empty line holds the headings
+/// and every other line is one record (see [`Grid`]). Lines come with their
+/// number, which `location` turns into a `Location` together with the column.
+pub(crate) fn read_records<I, L>(
+    options: &ReadOptions,
+    lines: I,
+    location: L,
+) -> Vec<RowOrParagraph>
+where
+    I: Iterator<Item = (usize, Vec<String>)>,
+    L: Fn(usize, usize) -> Location,
+{
+    let mut items = Vec::new();
+    let mut warnings = Vec::new();
+    let mut lines = lines.filter(|(_, cells)| cells.iter().any(|c| !c.trim().is_empty()));
+
+    let (heading_line, headings) = match lines.next() {
+        Some(headings) => headings,
+        None => return items,
+    };
+
+    let grid = Grid::new(
+        options.schema.clone(),
+        headings,
+        |col| location(heading_line, col),
+        &mut warnings,
+    );
+
+    items.extend(warnings.drain(..).map(RowOrParagraph::Warning));
+
+    if !grid.names_fields() {
+        return items;
+    }
+
+    for (line, cells) in lines {
+        let row = grid.record(
+            options.file.as_ref(),
+            cells,
+            |col| location(line, col),
+            &mut warnings,
+        );
+
+        items.extend(warnings.drain(..).map(RowOrParagraph::Warning));
+        items.push(RowOrParagraph::Row(row));
+    }
+
+    items
+}
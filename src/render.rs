@@ -0,0 +1,32 @@
+use std::io::Write;
+
+use crate::data::Row;
+use crate::CallError;
+
+/// Turns the items read from the documents into an output file.
+///
+/// The caller drives the renderer: `start_document` once, then `section`,
+/// `row` and `paragraph` in catalog order and `end_document` at the end.
+/// Every hook but `row` does nothing by default, so formats that only care
+/// about rows (CSV, JSON) just implement that.
+pub trait Renderer {
+    fn start_document(&mut self, _w: &mut dyn Write, _title: &str) -> Result<(), CallError> {
+        Ok(())
+    }
+
+    /// Starts a new section, `heading` being its title.
+    fn section(&mut self, _w: &mut dyn Write, _heading: &str) -> Result<(), CallError> {
+        Ok(())
+    }
+
+    fn row(&mut self, w: &mut dyn Write, row: &Row) -> Result<(), CallError>;
+
+    /// Text found between the tables of a document.
+    fn paragraph(&mut self, _w: &mut dyn Write, _text: &str) -> Result<(), CallError> {
+        Ok(())
+    }
+
+    fn end_document(&mut self, _w: &mut dyn Write) -> Result<(), CallError> {
+        Ok(())
+    }
+}
@@ -0,0 +1,49 @@
+use std::io::{self, Write};
+
+use crate::data::Row;
+use crate::render::Renderer;
+use crate::CallError;
+
+/// Lists the comments of reviewers, one line per comment after where the
+/// value it is on was read, as in `a.docx, table 3 (Tabela 57), row 2:
+/// Página(s): ver a ata (Maria, 2024-03-01T10:00:00Z)`. Rows without
+/// comments are left out.
+#[derive(Default)]
+pub struct ReviewRenderer;
+
+impl ReviewRenderer {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn write_comments(&mut self, w: &mut dyn Write, row: &Row) -> io::Result<()> {
+        for (name, comment) in row.comments() {
+            if let Some(source) = row.provenance(name) {
+                write!(w, "{}: ", source)?;
+            }
+
+            let label = match row.schema().position(name) {
+                Some(i) => &row.schema().fields()[i].label,
+                None => name,
+            };
+
+            write!(w, "{}: {}", label, comment.text)?;
+
+            match (comment.author.as_ref(), comment.date.as_ref()) {
+                (Some(author), Some(date)) => write!(w, " ({}, {})", author, date)?,
+                (Some(by), None) | (None, Some(by)) => write!(w, " ({})", by)?,
+                (None, None) => {}
+            }
+
+            writeln!(w)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Renderer for ReviewRenderer {
+    fn row(&mut self, w: &mut dyn Write, row: &Row) -> Result<(), CallError> {
+        self.write_comments(w, row).map_err(CallError::Output)
+    }
+}
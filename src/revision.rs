@@ -0,0 +1,148 @@
+use std::fmt::{self, Display};
+use std::io::Read;
+use std::str::FromStr;
+
+use xml::reader::{EventReader, XmlEvent};
+
+use crate::format::{Element, Format};
+use crate::CallError;
+
+/// What is done with the revisions a Word document still tracks (`w:ins`,
+/// `w:del`), as left by reviewers who did not accept nor reject them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RevisionMode {
+    /// Read the document as if every revision was accepted: inserted text is
+    /// kept, deleted text is dropped.
+    Accept,
+    /// Read the document as if every revision was rejected: deleted text is
+    /// kept, inserted text is dropped.
+    Reject,
+    /// Refuse documents with revisions, listing them. A parser given the
+    /// document body alone stops at the first one.
+    Fail,
+}
+
+impl FromStr for RevisionMode {
+    type Err = String;
+
+    /// `accept`, `reject` or `fail`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "accept" => Ok(Self::Accept),
+            "reject" => Ok(Self::Reject),
+            "fail" => Ok(Self::Fail),
+            s => Err(s.to_owned()),
+        }
+    }
+}
+
+impl RevisionMode {
+    /// Whether the content of a revision of this kind is left out of the
+    /// text read.
+    pub(crate) fn drops(self, kind: RevisionKind) -> bool {
+        match self {
+            RevisionMode::Accept | RevisionMode::Fail => kind == RevisionKind::Deletion,
+            RevisionMode::Reject => kind == RevisionKind::Insertion,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RevisionKind {
+    /// Text inserted, or moved to its place (`w:ins`, `w:moveTo`).
+    Insertion,
+    /// Text deleted, or moved away from its place (`w:del`, `w:moveFrom`).
+    Deletion,
+}
+
+/// A revision still pending in a document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Revision {
+    pub kind: RevisionKind,
+    pub author: Option<String>,
+    /// Date of the revision as recorded, `2024-03-01T10:00:00Z`.
+    pub date: Option<String>,
+    /// The text inserted or deleted, empty for revisions of the layout (an
+    /// inserted row, a deleted paragraph mark).
+    pub text: String,
+}
+
+impl Display for Revision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            RevisionKind::Insertion => f.write_str("insertion")?,
+            RevisionKind::Deletion => f.write_str("deletion")?,
+        }
+
+        if !self.text.is_empty() {
+            write!(f, " of {:?}", self.text)?;
+        }
+
+        write!(
+            f,
+            " by {}",
+            self.author.as_deref().unwrap_or("an unknown author")
+        )?;
+
+        if let Some(date) = self.date.as_ref() {
+            write!(f, " on {}", date)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Lists the revisions of a document body, in document order. Revisions
+/// nested in another one are part of it.
+pub(crate) fn pending_revisions<R: Read>(r: R, format: Format) -> Result<Vec<Revision>, CallError> {
+    let mut revisions = Vec::new();
+    let mut depth = 0;
+
+    for event in EventReader::new(r) {
+        match event? {
+            XmlEvent::StartElement {
+                name, attributes, ..
+            } => match format.element(&name, &attributes) {
+                Element::Revision(kind, author, date) => {
+                    if depth == 0 {
+                        revisions.push(Revision {
+                            kind,
+                            author,
+                            date,
+                            text: String::new(),
+                        });
+                    }
+
+                    depth += 1;
+                }
+                Element::Text(true) | Element::Space(_) if depth > 0 => {
+                    if let Some(revision) = revisions.last_mut() {
+                        revision.text.push(' ');
+                    }
+                }
+                _ => {}
+            },
+            XmlEvent::EndElement { name } => {
+                if let Element::Revision(..) = format.element(&name, &[]) {
+                    depth -= 1;
+                }
+            }
+            XmlEvent::Characters(text) if depth > 0 => {
+                if let Some(revision) = revisions.last_mut() {
+                    revision.text.push_str(&text);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    for revision in revisions.iter_mut() {
+        revision.text = revision
+            .text
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+    }
+
+    Ok(revisions)
+}
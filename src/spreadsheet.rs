@@ -0,0 +1,128 @@
+use std::io::{Cursor, Read};
+
+use calamine::{open_workbook_auto_from_rs, Data, Reader};
+use chrono::NaiveTime;
+
+use crate::diagnostic::Location;
+use crate::records::read_records;
+use crate::{CallError, ReadOptions, RowOrParagraph};
+
+/// Reads catalog records from a spreadsheet (xlsx, xls or ods).
+///
+/// In every sheet the first non empty row holds the headings, named like the
+/// keys of the document tables ("Ano da Revista", "Quem doou?", ...) and
+/// classified by the schema of `options`, and each following row is one
+/// record. Headings that name no field are reported once as warnings and
+/// their columns are kept as extra fields.
+pub fn read_spreadsheet<R: Read>(
+    mut r: R,
+    options: &ReadOptions,
+) -> Result<Vec<RowOrParagraph>, CallError> {
+    let mut data = Vec::new();
+    r.read_to_end(&mut data)?;
+
+    let mut workbook = open_workbook_auto_from_rs(Cursor::new(data))?;
+    let mut items = Vec::new();
+
+    for (sheet, (_, range)) in workbook.worksheets().into_iter().enumerate() {
+        let (first_row, first_col) = range.start().unwrap_or((0, 0));
+
+        let lines = range
+            .rows()
+            .map(|cells| cells.iter().map(cell_text).collect())
+            .enumerate();
+
+        items.extend(read_records(options, lines, |row, cell| Location {
+            table: sheet + 1,
+            caption: None,
+            row: first_row as usize + row + 1,
+            cell: first_col as usize + cell + 1,
+            position: None,
+        }));
+    }
+
+    Ok(items)
+}
+
+/// Text of a cell. Excel keeps dates as a number of days, they are written
+/// out as `1913-05-09`, with the time when it is not midnight.
+fn cell_text(cell: &Data) -> String {
+    match cell {
+        Data::DateTime(date) if date.is_datetime() => match date.as_datetime() {
+            Some(date) if date.time() == NaiveTime::MIN => date.format("%Y-%m-%d").to_string(),
+            Some(date) => date.format("%Y-%m-%d %H:%M:%S").to_string(),
+            None => cell.to_string(),
+        },
+        _ => cell.to_string(),
+    }
+}
+
+#[test]
+fn read_xlsx() {
+    use std::io::Write;
+    use zip::ZipWriter;
+
+    let parts = [
+        (
+            "[Content_Types].xml",
+            "<Types xmlns=\"http://schemas.openxmlformats.org/package/2006/content-types\">\
+             <Override PartName=\"/xl/workbook.xml\" \
+             ContentType=\"application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml\"/>\
+             </Types>",
+        ),
+        (
+            "xl/workbook.xml",
+            "<workbook xmlns=\"http://schemas.openxmlformats.org/spreadsheetml/2006/main\" \
+             xmlns:r=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships\">\
+             <sheets><sheet name=\"Doações\" sheetId=\"1\" r:id=\"rId1\"/></sheets></workbook>",
+        ),
+        (
+            "xl/_rels/workbook.xml.rels",
+            "<Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">\
+             <Relationship Id=\"rId1\" \
+             Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet\" \
+             Target=\"worksheets/sheet1.xml\"/></Relationships>",
+        ),
+        (
+            "xl/styles.xml",
+            "<styleSheet xmlns=\"http://schemas.openxmlformats.org/spreadsheetml/2006/main\">\
+             <cellXfs count=\"2\"><xf numFmtId=\"0\"/><xf numFmtId=\"14\"/></cellXfs></styleSheet>",
+        ),
+        (
+            "xl/worksheets/sheet1.xml",
+            "<worksheet xmlns=\"http://schemas.openxmlformats.org/spreadsheetml/2006/main\"><sheetData>\
+             <row r=\"1\"><c r=\"A1\" t=\"inlineStr\"><is><t>Ano da Revista</t></is></c>\
+             <c r=\"B1\" t=\"inlineStr\"><is><t>Página</t></is></c>\
+             <c r=\"C1\" t=\"inlineStr\"><is><t>Recebido em</t></is></c></row>\
+             <row r=\"2\"><c r=\"A2\"><v>1913</v></c>\
+             <c r=\"B2\" t=\"inlineStr\"><is><t>p.7</t></is></c>\
+             <c r=\"C2\" s=\"1\"><v>4878</v></c></row></sheetData></worksheet>",
+        ),
+    ];
+
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+
+    for (name, content) in parts.iter() {
+        zip.start_file(*name, Default::default()).unwrap();
+        zip.write_all(content.as_bytes()).unwrap();
+    }
+
+    let data = zip.finish().unwrap().into_inner();
+    let items = read_spreadsheet(&data[..], &ReadOptions::default()).unwrap();
+
+    let rows: Vec<_> = items
+        .iter()
+        .filter_map(|item| match item {
+            RowOrParagraph::Row(row) => Some(row),
+            _ => None,
+        })
+        .collect();
+
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].get("year").unwrap().to_string(), "1913");
+    assert_eq!(rows[0].get("page").unwrap().to_string(), "p.~7");
+    assert_eq!(
+        rows[0].extras(),
+        &[("Recebido em".to_owned(), "1913-05-09".to_owned())]
+    );
+}